@@ -0,0 +1,99 @@
+use std::io::{self, BufWriter, Write};
+use std::env;
+use std::str;
+use std::time::Instant;
+use common::input::{lines, MappedInput};
+use common::random::Rng;
+use common::solver::SolveError;
+use day1::line_summary::LineCheck;
+use day1::Mode;
+
+const USAGE: &str = "usage: bench_calibration FILE
+       bench_calibration --generate MEGABYTES [--seed N] > FILE
+times reading the calibration values of a document in both parts, with the scanner line by line and
+over the whole document (with and without summarising its lines), and with the implementation before it, or generates a document to time them on";
+
+// Reads the calibration value of a line, one way or another
+type ReadValue = fn(&str, &Mode) -> Result<Option<u64>, SolveError>;
+
+/*
+ * Sums the calibration values of the document's lines, each read by value.
+ */
+fn sum_with(bytes: &[u8], mode: &Mode, value: ReadValue) -> Result<u64, SolveError> {
+    let mut sum: u64 = 0;
+    for line in lines(bytes) {
+        let line = str::from_utf8(line).map_err(|error| SolveError::new(error.to_string()))?;
+        if let Some(value) = value(line.trim(), mode)? {
+            sum = sum.checked_add(value).ok_or_else(|| SolveError::new("overflow while summing calibration values"))?;
+        }
+    }
+    Ok(sum)
+}
+
+fn generate(megabytes: u64, seed: u64) -> io::Result<()> {
+    let mut rng = Rng::new(seed);
+    let mut output = BufWriter::new(io::stdout().lock());
+    let mut written = 0;
+    while written < megabytes * 1_000_000 {
+        let chunk = day1::generate_document(&mut rng, 10_000);
+        output.write_all(chunk.as_bytes())?;
+        written += chunk.len() as u64;
+    }
+    output.flush()
+}
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().collect();
+    let usage_error = || io::Error::new(io::ErrorKind::InvalidInput, USAGE);
+    match &args[1..] {
+        [generate_flag, megabytes, rest @ ..] if generate_flag == "--generate" => {
+            let megabytes = megabytes.parse().map_err(|_| usage_error())?;
+            let seed = match rest {
+                [] => 2023,
+                [seed_flag, seed] if seed_flag == "--seed" => seed.parse().map_err(|_| usage_error())?,
+                _ => return Err(usage_error()),
+            };
+            return generate(megabytes, seed);
+        }
+        [file_path] if !file_path.starts_with("--") => {
+            let input = MappedInput::open(file_path)?;
+            let bytes = input.bytes();
+            let megabytes = bytes.len() as f64 / 1e6;
+            println!("{:.0}MB, {} lines", megabytes, lines(bytes).count());
+
+            // Reading every byte once, and splitting the lines, as the least any implementation can take
+            let started = Instant::now();
+            let newlines = bytes.iter().filter(|&&byte| byte == b'\n').count();
+            let elapsed = started.elapsed().as_secs_f64();
+            println!("bytes only     {:>8.3}s {:>8.0}MB/s  ({} newlines)", elapsed, megabytes / elapsed, newlines);
+
+            let started = Instant::now();
+            let read: usize = lines(bytes).filter_map(|line| str::from_utf8(line).ok()).map(|line| line.trim().len()).sum();
+            let elapsed = started.elapsed().as_secs_f64();
+            println!("lines only     {:>8.3}s {:>8.0}MB/s  ({} bytes)", elapsed, megabytes / elapsed, read);
+
+            for part in 1..=2 {
+                let mode = Mode::part(part);
+                let implementations: [(&str, ReadValue); 2] = [
+                    ("collected", day1::calibration_value_collected),
+                    ("scanner", |line, mode| Ok(day1::calibration_value(line, mode)?.map(|calibration| calibration.value))),
+                ];
+                for (name, value) in implementations {
+                    let started = Instant::now();
+                    let sum = sum_with(bytes, &mode, value)?;
+                    let elapsed = started.elapsed().as_secs_f64();
+                    println!("part {} {:<9} {:>8.3}s {:>8.0}MB/s  Sum: {}", part, name, elapsed, megabytes / elapsed, sum);
+                }
+                for (name, check) in [("document", LineCheck::Off), ("summary", LineCheck::Summary)] {
+                    let started = Instant::now();
+                    let solution = day1::sum_calibration_document(bytes, &mode, check)?;
+                    let elapsed = started.elapsed().as_secs_f64();
+                    println!("part {} {:<9} {:>8.3}s {:>8.0}MB/s  Sum: {}", part, name, elapsed, megabytes / elapsed, solution.answer);
+                }
+            }
+        }
+        _ => return Err(usage_error()),
+    }
+
+    Ok(())
+}
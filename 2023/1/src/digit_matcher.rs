@@ -0,0 +1,179 @@
+use std::collections::VecDeque;
+
+/*
+ * Finds the digits in a calibration line, whether written as digits or spelled out, in a single
+ * pass over the line.
+ *
+ * The tokens to look for are compiled into an Aho-Corasick automaton: a trie of the tokens, with
+ * each node also knowing where to continue for every byte that does not extend its token, so that
+ * every token is found as its last byte is read, including tokens that overlap (both "eight" and
+ * "two" in "eightwo").
+ */
+
+/*
+ * A token found in a line: the span of bytes it covers, and the digit it stands for.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+}
+
+pub const DIGITS: [(&str, u32); 10] = [
+    ("0", 0), ("1", 1), ("2", 2), ("3", 3), ("4", 4), ("5", 5), ("6", 6), ("7", 7), ("8", 8), ("9", 9),
+];
+
+// Marks a byte with no edge in the trie while it is being built
+const NO_NODE: usize = usize::MAX;
+
+struct Node {
+    // The node to move to for each byte read
+    next: [usize; 256],
+    // The tokens that end here, as (length, value)
+    outputs: Vec<(usize, u32)>,
+}
+
+impl Node {
+    fn new() -> Node {
+        Node { next: [NO_NODE; 256], outputs: Vec::new() }
+    }
+}
+
+pub struct DigitMatcher {
+    nodes: Vec<Node>,
+}
+
+impl DigitMatcher {
+    pub fn new<S: AsRef<str>>(tokens: &[(S, u32)]) -> DigitMatcher {
+        let mut nodes = vec![Node::new()];
+        for &(ref token, value) in tokens {
+            let token = token.as_ref();
+            let mut node = 0;
+            for &byte in token.as_bytes() {
+                if nodes[node].next[byte as usize] == NO_NODE {
+                    nodes.push(Node::new());
+                    let child = nodes.len() - 1;
+                    nodes[node].next[byte as usize] = child;
+                }
+                node = nodes[node].next[byte as usize];
+            }
+            nodes[node].outputs.push((token.len(), value));
+        }
+
+        // Breadth first, so that the node a byte fails over to (the longest proper suffix of the
+        // node's token that is in the trie) is complete before the nodes that fail over to it
+        let mut fail = vec![0; nodes.len()];
+        let mut queue = VecDeque::new();
+        for byte in 0..256 {
+            match nodes[0].next[byte] {
+                NO_NODE => nodes[0].next[byte] = 0,
+                child => queue.push_back(child),
+            }
+        }
+        while let Some(node) = queue.pop_front() {
+            // Tokens ending at the failure node are suffixes of those ending here, so shorter
+            let inherited = nodes[fail[node]].outputs.clone();
+            nodes[node].outputs.extend(inherited);
+            for byte in 0..256 {
+                let via_fail = nodes[fail[node]].next[byte];
+                match nodes[node].next[byte] {
+                    NO_NODE => nodes[node].next[byte] = via_fail,
+                    child => {
+                        fail[child] = via_fail;
+                        queue.push_back(child);
+                    }
+                }
+            }
+        }
+        DigitMatcher { nodes }
+    }
+
+    /*
+     * Calls found with every token in the line, in the order they end (longest first where several
+     * end together).
+     */
+    pub fn for_each_match<F: FnMut(Match)>(&self, line: &[u8], mut found: F) {
+        let mut node = 0;
+        for (i, &byte) in line.iter().enumerate() {
+            node = self.nodes[node].next[byte as usize];
+            for &(length, value) in &self.nodes[node].outputs {
+                found(Match { start: i + 1 - length, end: i + 1, value });
+            }
+        }
+    }
+
+    /*
+     * The first and last tokens in the line, or None if it has none. Of tokens starting at the
+     * same place, the longest is taken, either way.
+     */
+    pub fn first_and_last(&self, line: &[u8]) -> Option<(Match, Match)> {
+        let mut first: Option<Match> = None;
+        let mut last: Option<Match> = None;
+        self.for_each_match(line, |found| {
+            let longer = |other: Match| found.end - found.start > other.end - other.start;
+            if first.is_none_or(|first| found.start < first.start || (found.start == first.start && longer(first))) {
+                first = Some(found);
+            }
+            if last.is_none_or(|last| found.start > last.start || (found.start == last.start && longer(last))) {
+                last = Some(found);
+            }
+        });
+        first.map(|first| (first, last.expect("a first token is also a last")))
+    }
+}
+
+#[cfg(test)]
+mod digit_matcher_tests {
+    use super::*;
+
+    fn english() -> DigitMatcher {
+        let english = [
+            ("zero", 0), ("one", 1), ("two", 2), ("three", 3), ("four", 4),
+            ("five", 5), ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9),
+        ];
+        DigitMatcher::new(&[&DIGITS[..], &english[..]].concat())
+    }
+
+    fn matches(matcher: &DigitMatcher, line: &str) -> Vec<Match> {
+        let mut matches = Vec::new();
+        matcher.for_each_match(line.as_bytes(), |found| matches.push(found));
+        matches
+    }
+
+    fn values(matches: &[Match]) -> Vec<u32> {
+        matches.iter().map(|found| found.value).collect()
+    }
+
+    #[test]
+    fn test_overlapping_tokens() {
+        let matcher = english();
+        assert_eq!(values(&matches(&matcher, "eightwothree")), vec![8, 2, 3]);
+        assert_eq!(values(&matches(&matcher, "xtwone3four")), vec![2, 1, 3, 4]);
+        assert_eq!(values(&matches(&matcher, "oneighthreeight")), vec![1, 8, 3, 8]);
+        assert_eq!(matches(&matcher, "nineight")[1], Match { start: 3, end: 8, value: 8 });
+        assert_eq!(matches(&matcher, "no digits"), vec![]);
+    }
+
+    #[test]
+    fn test_first_and_last() {
+        let matcher = english();
+        let first_and_last = |line: &str| {
+            matcher.first_and_last(line.as_bytes()).map(|(first, last)| (first.start..first.end, last.start..last.end))
+        };
+        assert_eq!(first_and_last("eightwothree"), Some((0..5, 7..12)));
+        assert_eq!(first_and_last("zoneight234"), Some((1..4, 10..11)));
+        assert_eq!(first_and_last("twone"), Some((0..3, 2..5)));
+        assert_eq!(first_and_last("7pqrstsixteen"), Some((0..1, 6..9)));
+        assert_eq!(first_and_last("treb7uchet"), Some((4..5, 4..5)));
+        assert_eq!(first_and_last("trebuchet"), None);
+    }
+
+    #[test]
+    fn test_longer_token_preferred() {
+        let matcher = DigitMatcher::new(&[("seven", 7), ("seventeen", 17), ("teen", 10)]);
+        let (first, last) = matcher.first_and_last(b"xseventeen").unwrap();
+        assert_eq!((first.value, last.value), (17, 10));
+        assert_eq!(values(&matches(&matcher, "seventeen")), vec![7, 17, 10]);
+    }
+}
@@ -0,0 +1,98 @@
+use std::ops::Range;
+
+/*
+ * Shows which parts of a calibration line its value came from: the line as it is, with the span
+ * that gave the first digit and the span that gave the last highlighted.
+ *
+ * On a terminal the spans are coloured, the first green and the last blue, with any bytes they
+ * share (as in "twone", or a lone digit that is both) magenta. Otherwise the first is marked
+ * [like this] and the last {like this}, or only [like this] where they are the same span.
+ */
+
+const FIRST: &str = "\x1b[1;32m";
+const LAST: &str = "\x1b[1;34m";
+const BOTH: &str = "\x1b[1;35m";
+const RESET: &str = "\x1b[0m";
+
+fn coloured(line: &str, first: &Range<usize>, last: &Range<usize>) -> String {
+    let colour = |i: usize| match (first.contains(&i), last.contains(&i)) {
+        (true, true) => Some(BOTH),
+        (true, false) => Some(FIRST),
+        (false, true) => Some(LAST),
+        (false, false) => None,
+    };
+    let mut highlighted = String::new();
+    let mut current = None;
+    for (i, c) in line.char_indices() {
+        if colour(i) != current {
+            if current.is_some() {
+                highlighted.push_str(RESET);
+            }
+            current = colour(i);
+            highlighted.push_str(current.unwrap_or(""));
+        }
+        highlighted.push(c);
+    }
+    if current.is_some() {
+        highlighted.push_str(RESET);
+    }
+    highlighted
+}
+
+fn marked(line: &str, first: &Range<usize>, last: &Range<usize>) -> String {
+    let same = first == last;
+    let mut highlighted = String::new();
+    for i in 0..=line.len() {
+        // Closing before opening, so that spans which only touch are not nested
+        if i == first.end {
+            highlighted.push(']');
+        }
+        if i == last.end && !same {
+            highlighted.push('}');
+        }
+        if i == first.start {
+            highlighted.push('[');
+        }
+        if i == last.start && !same {
+            highlighted.push('{');
+        }
+        if let Some(c) = line.get(i..).and_then(|rest| rest.chars().next()) {
+            highlighted.push(c);
+        }
+    }
+    highlighted
+}
+
+/*
+ * The line with the first and last spans (byte ranges on character boundaries) highlighted, in
+ * colour or with markers.
+ */
+pub fn highlight(line: &str, first: Range<usize>, last: Range<usize>, colour: bool) -> String {
+    if colour {
+        coloured(line, &first, &last)
+    } else {
+        marked(line, &first, &last)
+    }
+}
+
+#[cfg(test)]
+mod explain_tests {
+    use super::*;
+
+    #[test]
+    fn test_markers() {
+        assert_eq!(highlight("two1nine", 0..3, 4..8, false), "[two]1{nine}");
+        assert_eq!(highlight("treb7uchet", 4..5, 4..5, false), "treb[7]uchet");
+        assert_eq!(highlight("twone", 0..3, 2..5, false), "[tw{o]ne}");
+        assert_eq!(highlight("eightwo", 0..5, 4..7, false), "[eigh{t]wo}");
+        assert_eq!(highlight("12", 0..1, 1..2, false), "[1]{2}");
+        assert_eq!(highlight("zéro9", 0..5, 5..6, false), "[zéro]{9}");
+    }
+
+    #[test]
+    fn test_colours() {
+        assert_eq!(highlight("a1b2", 1..2, 3..4, true), "a\x1b[1;32m1\x1b[0mb\x1b[1;34m2\x1b[0m");
+        assert_eq!(highlight("twone", 0..3, 2..5, true), "\x1b[1;32mtw\x1b[0m\x1b[1;35mo\x1b[0m\x1b[1;34mne\x1b[0m");
+        assert_eq!(highlight("7", 0..1, 0..1, true), "\x1b[1;35m7\x1b[0m");
+    }
+}
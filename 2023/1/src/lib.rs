@@ -0,0 +1,466 @@
+use std::borrow::Cow;
+use std::str;
+use common::answer::{self, Answer};
+use common::budget::checkpoint;
+use common::grammar::{self, Grammar, Token};
+use common::input::lines;
+use common::random::Rng;
+use common::solver::{Solution, SolveError};
+
+pub mod digit_matcher;
+pub mod explain;
+pub mod line_summary;
+pub mod number_words;
+pub mod scanner;
+pub mod spelling;
+pub mod unicode_digits;
+pub mod vocabulary;
+
+use digit_matcher::{DigitMatcher, Match, DIGITS};
+use line_summary::{LineCheck, LineSummary};
+use number_words::{Number, Rule, COMPOUND_WORDS};
+use scanner::Scanner;
+use spelling::{Fuzzy, FuzzyMatch, Spelling};
+use unicode_digits::DigitPolicy;
+
+const PRINTABLE: Token = Token::Char(|c| !c.is_control(), "printable character");
+
+/*
+ * A line of printable characters per line of the document: letters, with digits (spelled out, in
+ * part 2) among them, in any script. Lines with no digit follow the grammar, as they are only an
+ * error with --strict, but blank lines and control characters such as tabs do not.
+ */
+pub const GRAMMAR: Grammar = Grammar(&[grammar::Rule::Lines(&[PRINTABLE, Token::Many(&[PRINTABLE])])]);
+
+/*
+ * How the calibration value of a line is read.
+ */
+pub enum Mode {
+    // Part 1: only digits written as digits
+    Digits { policy: DigitPolicy, scanner: Scanner },
+    // Part 2: digits and spelled-out digits (or with compound, numbers). The scanner finds the
+    // first and last single digits, the matcher every number for the other rules or where words
+    // may be misspelt (and fuzzy, then, the misspellings).
+    Words {
+        policy: DigitPolicy,
+        scanner: Scanner,
+        matcher: DigitMatcher,
+        compound: bool,
+        rule: Rule,
+        ignore_case: bool,
+        fuzzy: Option<Fuzzy>,
+    },
+}
+
+fn digit_tokens(policy: DigitPolicy) -> Vec<(String, u32)> {
+    DIGITS.iter().map(|&(digit, value)| (digit.to_string(), value)).chain(policy.non_ascii_tokens()).collect()
+}
+
+impl Mode {
+    /*
+     * The puzzle's own mode for a part: ASCII digits, and for part 2 English words as well.
+     */
+    pub fn part(part: u8) -> Mode {
+        if part == 1 {
+            Mode::digits(DigitPolicy::Ascii)
+        } else {
+            let english = vocabulary::ENGLISH.iter().map(|&(token, value)| (token.to_string(), value)).collect();
+            Mode::words(DigitPolicy::Ascii, english, false, Rule::Digit, Spelling::default())
+        }
+    }
+
+    /*
+     * Part 1's mode, with the digits of the policy.
+     */
+    pub fn digits(policy: DigitPolicy) -> Mode {
+        Mode::Digits { policy, scanner: Scanner::new(&digit_tokens(policy)) }
+    }
+
+    /*
+     * Part 2's mode, with the words of the vocabulary given (see vocabulary::combine), spelled as
+     * the spelling allows.
+     */
+    pub fn words(policy: DigitPolicy, vocabulary: Vec<(String, u32)>, compound: bool, rule: Rule, spelling: Spelling) -> Mode {
+        let vocabulary: Vec<(String, u32)> = if spelling.ignore_case {
+            vocabulary.into_iter().map(|(word, value)| (spelling::fold_case(&word).into_owned(), value)).collect()
+        } else {
+            vocabulary
+        };
+        let fuzzy = (spelling.max_edits > 0).then(|| Fuzzy::new(&vocabulary, spelling.max_edits));
+        let mut tokens = digit_tokens(policy);
+        tokens.extend(vocabulary);
+        let scanner = Scanner::new(&tokens);
+        if compound {
+            tokens.extend(COMPOUND_WORDS.iter().map(|&(word, value)| (word.to_string(), value)));
+        }
+        Mode::Words { policy, scanner, matcher: DigitMatcher::new(&tokens), compound, rule, ignore_case: spelling.ignore_case, fuzzy }
+    }
+
+    pub fn policy(&self) -> DigitPolicy {
+        match *self {
+            Mode::Digits { policy, .. } | Mode::Words { policy, .. } => policy,
+        }
+    }
+}
+
+/*
+ * A line's calibration value, and the first and last numbers it was made from.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calibration {
+    pub first: Number,
+    pub last: Number,
+    pub value: u64,
+    // The misspelt words read in the line, if the mode reads them
+    pub fuzzy: Vec<FuzzyMatch>,
+}
+
+// A single digit token as a number, its digit borrowed rather than allocated
+fn single_digit(found: Match) -> Number {
+    Number { start: found.start, end: found.end, digits: Cow::Borrowed(DIGITS[found.value as usize].0) }
+}
+
+/*
+ * The calibration value of a line, or None if it has no digits.
+ */
+pub fn calibration_value(line: &str, mode: &Mode) -> Result<Option<Calibration>, SolveError> {
+    // Folding keeps every character's span, so spans found in the folded line are spans of the line
+    let folded = match mode {
+        Mode::Words { ignore_case: true, .. } => spelling::fold_case(line),
+        _ => Cow::Borrowed(line),
+    };
+    let (first, last, rule, fuzzy) = match mode {
+        // Single digits by the puzzle's rule need only the first and last tokens
+        Mode::Digits { scanner, .. } | Mode::Words { scanner, compound: false, rule: Rule::Digit, fuzzy: None, .. } => {
+            let Some((first, last)) = scanner.first_and_last(folded.as_bytes()) else {
+                return Ok(None);
+            };
+            let value = (first.value * 10 + last.value) as u64;
+            return Ok(Some(Calibration { first: single_digit(first), last: single_digit(last), value, fuzzy: Vec::new() }));
+        }
+        Mode::Words { matcher, compound, rule, fuzzy, .. } => {
+            let mut numbers = number_words::numbers(matcher, folded.as_bytes(), *compound);
+            let fuzzy = match fuzzy {
+                Some(fuzzy) => spelling::merge(&mut numbers, fuzzy.matches(&folded)),
+                None => Vec::new(),
+            };
+            match (numbers.pop(), numbers.into_iter().next()) {
+                (Some(last), Some(first)) => (first, last, *rule, fuzzy),
+                (Some(only), None) => (only.clone(), only, *rule, fuzzy),
+                _ => return Ok(None),
+            }
+        }
+    };
+    let value = number_words::calibration_value(&first, &last, rule)
+        .ok_or_else(|| SolveError::new(format!("calibration value of {} too large", line)))?;
+    Ok(Some(Calibration { first, last, value, fuzzy }))
+}
+
+/*
+ * The calibration value of a line as it was read before the scanner: part 1 collecting every digit
+ * of the line to take the first and last, and part 2 running the matcher over all of it. Kept to
+ * check the scanner against, and to benchmark it.
+ */
+pub fn calibration_value_collected(line: &str, mode: &Mode) -> Result<Option<u64>, SolveError> {
+    match mode {
+        Mode::Digits { policy, .. } => {
+            let filtered_chars: Vec<u32> = line.chars().filter_map(|c| policy.digit_value(c)).collect();
+            match (filtered_chars.first(), filtered_chars.last()) {
+                (Some(first), Some(last)) => Ok(Some((first * 10 + last) as u64)),
+                _ => Ok(None),
+            }
+        }
+        Mode::Words { matcher, compound: false, rule: Rule::Digit, ignore_case: false, fuzzy: None, .. } => {
+            Ok(matcher.first_and_last(line.as_bytes()).map(|(first, last)| (first.value * 10 + last.value) as u64))
+        }
+        Mode::Words { .. } => Ok(calibration_value(line, mode)?.map(|calibration| calibration.value)),
+    }
+}
+
+/*
+ * Describes a number found, e.g. "eight at 0..5", or "twentythree (23) at 0..11" where its value
+ * has more than one digit.
+ */
+fn describe_number(line: &str, number: &Number) -> String {
+    let text = &line[number.start..number.end];
+    if number.digits.len() == 1 || text == number.digits {
+        format!("{} at {}..{}", text, number.start, number.end)
+    } else {
+        format!("{} ({}) at {}..{}", text, number.digits, number.start, number.end)
+    }
+}
+
+/*
+ * Describes where a calibration value came from, e.g. "first eight at 0..5, last three at 7..12".
+ */
+pub fn describe(line: &str, calibration: &Calibration) -> String {
+    format!("first {}, last {}", describe_number(line, &calibration.first), describe_number(line, &calibration.last))
+}
+
+// The bytes the scanner reads between checks of the run's budget, to the end of a line
+const PIECE_SIZE: usize = 1 << 20;
+
+/*
+ * Sums the calibration values of a document's lines, read in the mode given. The numeric
+ * characters that are not digits are reported in the diagnostics, followed by the summary of the
+ * lines with fewer than two digits unless the check is off. If strict, a line with no digit is an
+ * error.
+ *
+ * Where only the first and last single digits of a line matter, the scanner reads the document in
+ * one pass, rather than line by line.
+ */
+pub fn sum_calibration_document(text: &[u8], mode: &Mode, check: LineCheck) -> Result<Solution, SolveError> {
+    let line_number = |at: usize| text[..at].iter().filter(|&&byte| byte == b'\n').count() + 1;
+    str::from_utf8(text).map_err(|error| SolveError::new(format!("line {}: {}", line_number(error.valid_up_to()), error)))?;
+
+    let mut diagnostics = Vec::new();
+    if !text.is_ascii() {
+        for (i, line) in lines(text).enumerate() {
+            checkpoint()?;
+            let line = str::from_utf8(line).expect("the document is UTF-8").trim();
+            for (at, c) in mode.policy().non_digits(line) {
+                diagnostics.push(format!("line {}: {}, ignored", i + 1, unicode_digits::describe_non_digit(at, c)));
+            }
+        }
+    }
+
+    // Each value is below 100, so the sum of a document's values cannot overflow where they are
+    // summed as u64s
+    let mut summary = (check != LineCheck::Off).then(LineSummary::default);
+    let total = match mode {
+        Mode::Digits { policy: DigitPolicy::Ascii, .. } => {
+            Answer::from(scanner::sum_ascii_digit_lines(text, summary.as_mut())?)
+        }
+        Mode::Digits { scanner, .. } | Mode::Words { scanner, compound: false, rule: Rule::Digit, ignore_case: false, fuzzy: None, .. } => {
+            let (mut total, mut line_number): (u64, usize) = (0, 0);
+            // A piece of whole lines at a time, of about a megabyte, checking the budget before each
+            let mut start = 0;
+            while start < text.len() {
+                checkpoint()?;
+                let past = (start + PIECE_SIZE).min(text.len());
+                let end = text[past..].iter().position(|&byte| byte == b'\n').map_or(text.len(), |at| past + at + 1);
+                let piece = &text[start..end];
+                scanner.for_each_line(piece, |line, tokens| {
+                    line_number += 1;
+                    let digits = match tokens {
+                        Some((first, last)) => {
+                            total += (first.value * 10 + last.value) as u64;
+                            if first.start == last.start { 1 } else { 2 }
+                        }
+                        None => 0,
+                    };
+                    if let Some(summary) = &mut summary {
+                        summary.note(line_number, &piece[line], digits);
+                    }
+                });
+                start = end;
+            }
+            Answer::from(total)
+        }
+        Mode::Words { .. } => {
+            let mut total = Answer::from(0u64);
+            for (i, line) in lines(text).enumerate() {
+                checkpoint()?;
+                let line = str::from_utf8(line).expect("the document is UTF-8").trim();
+                let calibration = calibration_value(line, mode)?;
+                if let Some(summary) = &mut summary {
+                    summary.note_calibration(i + 1, line.as_bytes(), calibration.as_ref());
+                }
+                if let Some(calibration) = calibration {
+                    total = answer::add(total, Answer::from(calibration.value), "summing calibration values")?;
+                }
+            }
+            total
+        }
+    };
+
+    if let Some(summary) = summary {
+        if check == LineCheck::Strict {
+            summary.check_strict()?;
+        }
+        diagnostics.extend(summary.describe());
+    }
+    Ok(Solution::new(total, diagnostics))
+}
+
+/*
+ * Generates a calibration document of the number of lines given: letters, with digits and English
+ * spelled-out digits (which may overlap) among them. Some lines, by chance, have neither.
+ */
+pub fn generate_document(rng: &mut Rng, lines: usize) -> String {
+    const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+    let mut document = String::new();
+    for _ in 0..lines {
+        let pieces = rng.range(1..8);
+        for _ in 0..pieces {
+            match rng.range(0..4) {
+                0 => document.push(char::from(b'0' + rng.range(0..10) as u8)),
+                1 => document.push_str(vocabulary::ENGLISH[rng.range(0..10) as usize].0),
+                _ => document.push(char::from(LETTERS[rng.range(0..26) as usize])),
+            }
+        }
+        document.push('\n');
+    }
+    document
+}
+
+#[cfg(test)]
+mod calibration_value_tests {
+    use super::*;
+    use common::budget::Budget;
+    use std::time::Duration;
+
+    fn value(line: &str, mode: &Mode) -> Option<u64> {
+        calibration_value(line, mode).unwrap().map(|calibration| calibration.value)
+    }
+
+    #[test]
+    fn test_part_1_example() {
+        let mode = Mode::part(1);
+        assert_eq!(value("1abc2", &mode), Some(12));
+        assert_eq!(value("pqr3stu8vwx", &mode), Some(38));
+        assert_eq!(value("a1b2c3d4e5f", &mode), Some(15));
+        assert_eq!(value("treb7uchet", &mode), Some(77));
+        let example = include_bytes!("../example_calibration_document.txt");
+        assert_eq!(sum_calibration_document(example, &mode, LineCheck::Off).unwrap().answer, "142");
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        let mode = Mode::part(2);
+        assert_eq!(value("two1nine", &mode), Some(29));
+        assert_eq!(value("eightwothree", &mode), Some(83));
+        assert_eq!(value("abcone2threexyz", &mode), Some(13));
+        assert_eq!(value("xtwone3four", &mode), Some(24));
+        assert_eq!(value("4nineeightseven2", &mode), Some(42));
+        assert_eq!(value("zoneight234", &mode), Some(14));
+        assert_eq!(value("7pqrstsixteen", &mode), Some(76));
+        let example = include_bytes!("../example_calibration_document_2.txt");
+        assert_eq!(sum_calibration_document(example, &mode, LineCheck::Off).unwrap().answer, "281");
+    }
+
+    #[test]
+    fn test_part_1_ignores_words() {
+        let mode = Mode::part(1);
+        assert_eq!(value("two1nine", &mode), Some(11));
+        assert_eq!(value("eightwothree", &mode), None);
+        let example = include_bytes!("../example_calibration_document_2.txt");
+        assert_eq!(sum_calibration_document(example, &mode, LineCheck::Off).unwrap().answer, "209");
+    }
+
+    #[test]
+    fn test_modes() {
+        let compound = Mode::words(DigitPolicy::Ascii, vocabulary::load("english").unwrap(), true, Rule::Number, Spelling::default());
+        assert_eq!(value("eighteenine", &compound), Some(189));
+        assert_eq!(value("twentythree", &compound), Some(2323));
+        let calibration = calibration_value("xonehundredfive7", &compound).unwrap().unwrap();
+        assert_eq!(describe("xonehundredfive7", &calibration), "first onehundredfive (105) at 1..15, last 7 at 15..16");
+
+        let unicode = Mode::digits(DigitPolicy::Unicode);
+        assert_eq!(value("a٣b7½", &unicode), Some(37));
+        let example = include_bytes!("../example_calibration_document_unicode.txt");
+        let solution = sum_calibration_document(example, &Mode::part(1), LineCheck::Off).unwrap();
+        assert_eq!(solution.answer, "89");
+        assert_eq!(solution.diagnostics[1], "line 1: ½ at 5 is numeric but not a decimal digit, ignored");
+    }
+
+    #[test]
+    fn test_spelling() {
+        let example = include_bytes!("../example_calibration_document_ocr.txt");
+        let english = || vocabulary::load("english").unwrap();
+        let sum = |spelling: Spelling| {
+            sum_calibration_document(example, &Mode::words(DigitPolicy::Ascii, english(), false, Rule::Digit, spelling), LineCheck::Off).unwrap().answer
+        };
+        assert_eq!(sum(Spelling::default()), "99");
+        assert_eq!(sum(Spelling { ignore_case: true, max_edits: 0 }), "211");
+        assert_eq!(sum(Spelling { ignore_case: false, max_edits: 1 }), "187");
+        assert_eq!(sum(Spelling { ignore_case: true, max_edits: 1 }), "254");
+
+        let mode = Mode::words(DigitPolicy::Ascii, english(), false, Rule::Digit, Spelling { ignore_case: true, max_edits: 1 });
+        let calibration = calibration_value("twoSEVN", &mode).unwrap().unwrap();
+        assert_eq!(describe("twoSEVN", &calibration), "first two at 0..3, last SEVN at 3..7");
+        assert_eq!(calibration.fuzzy[0].describe("twoSEVN"), "SEVN at 3..7 read as seven (7), 1 edit, confidence 0.80");
+    }
+
+    #[test]
+    fn test_scanner_agrees_with_collected() {
+        let document = generate_document(&mut Rng::new(1), 2000) + "a٣b7½\r\nx٣y\n\n";
+        let modes = [Mode::part(1), Mode::part(2), Mode::digits(DigitPolicy::Unicode)];
+        for mode in &modes {
+            let mut sum = 0;
+            for line in document.lines() {
+                let collected = calibration_value_collected(line, mode).unwrap();
+                assert_eq!(value(line, mode), collected, "{}", line);
+                sum += collected.unwrap_or(0);
+            }
+            for check in [LineCheck::Off, LineCheck::Summary] {
+                assert_eq!(sum_calibration_document(document.as_bytes(), mode, check).unwrap().answer, sum.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_document_in_pieces() {
+        let document = generate_document(&mut Rng::new(2), 400_000);
+        assert!(document.len() > 2 * PIECE_SIZE);
+        let english = vocabulary::load("english").unwrap();
+        // Read line by line, as ignoring case only matters to lines with capitals
+        let line_by_line = Mode::words(DigitPolicy::Ascii, english, false, Rule::Digit, Spelling { ignore_case: true, max_edits: 0 });
+        let expected = sum_calibration_document(document.as_bytes(), &line_by_line, LineCheck::Summary).unwrap();
+        let solution = sum_calibration_document(document.as_bytes(), &Mode::part(2), LineCheck::Summary).unwrap();
+        assert_eq!((solution.answer, solution.diagnostics), (expected.answer, expected.diagnostics));
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let document = generate_document(&mut Rng::new(3), 100);
+        let _guard = Budget { timeout: Some(Duration::ZERO), max_memory: None }.install();
+        for mode in [Mode::part(1), Mode::part(2), Mode::digits(DigitPolicy::Unicode)] {
+            let error = sum_calibration_document(document.as_bytes(), &mode, LineCheck::Off).unwrap_err();
+            assert_eq!(error.to_string(), "cancelled: time limit of 0s exceeded");
+        }
+    }
+
+    #[test]
+    fn test_line_summary() {
+        let text = b"1abc\nxyz\n\ntreb7uchet\n  \r\nfour5six\nnone";
+        let part_1 = [
+            "2 lines with no digit: 2, 7",
+            "3 lines with one digit, used as both tens and units: 1, 4, 6",
+            "2 blank lines: 3, 5",
+        ];
+        let part_2 = [
+            "1 line with no digit: 2",
+            "3 lines with one digit, used as both tens and units: 1, 4, 7",
+            "2 blank lines: 3, 5",
+        ];
+        let english = || vocabulary::load("english").unwrap();
+        let ignore_case = Spelling { ignore_case: true, max_edits: 0 };
+        // The SWAR scanner, the scanner line by line, and the matcher
+        let modes = [
+            (Mode::part(1), &part_1),
+            (Mode::digits(DigitPolicy::Unicode), &part_1),
+            (Mode::part(2), &part_2),
+            (Mode::words(DigitPolicy::Ascii, english(), false, Rule::Digit, ignore_case), &part_2),
+        ];
+        for (mode, summary) in &modes {
+            assert_eq!(sum_calibration_document(text, mode, LineCheck::Summary).unwrap().diagnostics, summary[..]);
+            assert!(sum_calibration_document(text, mode, LineCheck::Off).unwrap().diagnostics.is_empty());
+        }
+
+        let error = sum_calibration_document(text, &Mode::part(1), LineCheck::Strict).unwrap_err();
+        assert_eq!(error.to_string(), "line 2 has no digit");
+        let error = sum_calibration_document(b"1\n \nx", &Mode::part(2), LineCheck::Strict).unwrap_err();
+        assert_eq!(error.to_string(), "line 2 is blank");
+        assert_eq!(sum_calibration_document(b"12\none", &Mode::part(2), LineCheck::Strict).unwrap().answer, "23");
+
+        let solution = sum_calibration_document(&[b'\n'; 25], &Mode::part(1), LineCheck::Summary).unwrap();
+        assert_eq!(solution.diagnostics, ["25 blank lines: 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20 and 5 more"]);
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let error = sum_calibration_document(b"1a\n2b\xff\n", &Mode::part(1), LineCheck::Off).unwrap_err();
+        assert_eq!(error.to_string(), "line 2: invalid utf-8 sequence of 1 bytes from index 5");
+    }
+}
@@ -0,0 +1,140 @@
+use std::str;
+use common::solver::SolveError;
+use crate::Calibration;
+
+// The line numbers of each kind a summary lists; the rest are only counted
+const LISTED: usize = 20;
+
+/*
+ * The numbers of the lines of one kind in a document: how many, and the first few.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineNumbers {
+    pub count: usize,
+    pub listed: Vec<usize>,
+}
+
+impl LineNumbers {
+    fn add(&mut self, line_number: usize) {
+        self.count += 1;
+        if self.listed.len() < LISTED {
+            self.listed.push(line_number);
+        }
+    }
+
+    // e.g. "2 lines with no digit: 3, 7", the kind given as one line and as more
+    fn describe(&self, one: &str, more: &str) -> String {
+        let listed: Vec<String> = self.listed.iter().map(usize::to_string).collect();
+        let rest = if self.count > listed.len() { format!(" and {} more", self.count - listed.len()) } else { String::new() };
+        format!("{} {}: {}{}", self.count, if self.count == 1 { one } else { more }, listed.join(", "), rest)
+    }
+}
+
+// Whether a line is only whitespace, decoding it only if it starts with whitespace or non-ASCII
+fn is_blank(line: &[u8]) -> bool {
+    match line.first() {
+        Some(&byte) if byte.is_ascii() && !(byte as char).is_whitespace() => false,
+        _ => str::from_utf8(line).is_ok_and(|line| line.trim().is_empty()),
+    }
+}
+
+/*
+ * What is made of the lines of a document whose values may not be what was meant: nothing, as when
+ * only the sum is wanted (the lines are not even counted, as that slows reading a document in one
+ * pass), a summary of them, or the summary with a line with no digit, or blank, an error.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCheck {
+    Off,
+    Summary,
+    Strict,
+}
+
+/*
+ * The lines of a document whose values may not be what was meant: lines with no digit, lines with
+ * one (used as both tens and units), and blank lines (which are not counted as having no digit).
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineSummary {
+    pub no_digit: LineNumbers,
+    pub one_digit: LineNumbers,
+    pub blank: LineNumbers,
+}
+
+impl LineSummary {
+    // Whether lines (not blank) with so many digits are still listed, rather than only counted
+    pub(crate) fn listing(&self, digits: usize) -> bool {
+        match digits {
+            0 => self.no_digit.listed.len() < LISTED,
+            _ => self.one_digit.listed.len() < LISTED,
+        }
+    }
+
+    /*
+     * Notes a line, by its number from 1, with how many digits it has (counting no further than
+     * two).
+     */
+    pub fn note(&mut self, line_number: usize, line: &[u8], digits: usize) {
+        match digits {
+            0 if is_blank(line) => self.blank.add(line_number),
+            0 => self.no_digit.add(line_number),
+            1 => self.one_digit.add(line_number),
+            _ => {}
+        }
+    }
+
+    /*
+     * Notes a line with its calibration value, if it has one. A run of digits, or a compound word,
+     * is one number but more than one digit.
+     */
+    pub fn note_calibration(&mut self, line_number: usize, line: &[u8], calibration: Option<&Calibration>) {
+        let digits = calibration.map_or(0, |Calibration { first, last, .. }| {
+            if first.start == last.start && first.digits.len() == 1 { 1 } else { 2 }
+        });
+        self.note(line_number, line, digits);
+    }
+
+    /*
+     * Describes each kind of line there are any of, e.g. "1 blank line: 5".
+     */
+    pub fn describe(&self) -> Vec<String> {
+        [
+            (&self.no_digit, "line with no digit", "lines with no digit"),
+            (&self.one_digit, "line with one digit, used as both tens and units", "lines with one digit, used as both tens and units"),
+            (&self.blank, "blank line", "blank lines"),
+        ]
+        .into_iter()
+        .filter(|(lines, _, _)| lines.count > 0)
+        .map(|(lines, one, more)| lines.describe(one, more))
+        .collect()
+    }
+
+    /*
+     * Under --strict, the first line with no digit, blank or not, is an error.
+     */
+    pub fn check_strict(&self) -> Result<(), SolveError> {
+        let no_digit = self.no_digit.listed.first().map(|&line_number| (line_number, "has no digit"));
+        let blank = self.blank.listed.first().map(|&line_number| (line_number, "is blank"));
+        match no_digit.into_iter().chain(blank).min() {
+            Some((line_number, what)) => Err(SolveError::new(format!("line {} {}", line_number, what))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod line_summary_tests {
+    use super::*;
+
+    #[test]
+    fn test_note() {
+        let mut summary = LineSummary::default();
+        for (i, (line, digits)) in [("abc", 0), ("", 0), (" \t\r", 0), ("a1b", 1), ("\u{3000}", 0), ("12", 2), ("\u{b}x", 0)].into_iter().enumerate() {
+            summary.note(i + 1, line.as_bytes(), digits);
+        }
+        assert_eq!(summary.no_digit.listed, [1, 7]);
+        assert_eq!(summary.one_digit.listed, [4]);
+        assert_eq!(summary.blank.listed, [2, 3, 5]);
+        assert_eq!(summary.check_strict().unwrap_err().to_string(), "line 1 has no digit");
+    }
+}
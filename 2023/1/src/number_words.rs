@@ -0,0 +1,207 @@
+use std::borrow::Cow;
+use crate::digit_matcher::{DigitMatcher, Match};
+
+/*
+ * Numbers in calibration lines, rather than single digits: runs of digits, and optionally English
+ * number words built up from parts, such as "twentythree" (23) or "onehundredfive" (105).
+ *
+ * The parts are found by a DigitMatcher over the tokens of COMPOUND_WORDS (with the digits and any
+ * other vocabulary), their values telling them apart: below 10 a unit, then teens, tens, and 100
+ * for "hundred". A number is the longest one the parts starting at a place make up, and numbers
+ * may overlap as single digits do ("eighteenine" has both 18 and 9), but one within another (the
+ * "three" of "twentythree") is not a number of its own.
+ */
+
+pub const COMPOUND_WORDS: [(&str, u32); 19] = [
+    ("ten", 10), ("eleven", 11), ("twelve", 12), ("thirteen", 13), ("fourteen", 14),
+    ("fifteen", 15), ("sixteen", 16), ("seventeen", 17), ("eighteen", 18), ("nineteen", 19),
+    ("twenty", 20), ("thirty", 30), ("forty", 40), ("fifty", 50), ("sixty", 60),
+    ("seventy", 70), ("eighty", 80), ("ninety", 90), ("hundred", 100),
+];
+
+/*
+ * How a calibration value is made from a line: from the first digit of the first number and the
+ * last digit of the last (as the puzzle does), or from the whole of the first and last numbers
+ * one after the other.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Digit,
+    Number,
+}
+
+/*
+ * A number found in a line: the span of bytes it covers, and its digits (borrowed where a single
+ * digit, so that finding one allocates nothing).
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub start: usize,
+    pub end: usize,
+    pub digits: Cow<'static, str>,
+}
+
+fn is_unit(token: &Match) -> bool {
+    token.value < 10
+}
+
+/*
+ * The ways a number below a hundred can be read from the parts starting at a place, as (end,
+ * value): a unit, a teen, or tens with or without a unit after them.
+ */
+fn below_hundred(starting: &[Vec<Match>], at: usize) -> Vec<(usize, u32)> {
+    let mut readings = Vec::new();
+    for token in starting.get(at).map_or(&[][..], Vec::as_slice) {
+        match token.value {
+            0..=19 => readings.push((token.end, token.value)),
+            20..=90 => {
+                readings.push((token.end, token.value));
+                for unit in starting.get(token.end).map_or(&[][..], Vec::as_slice) {
+                    if is_unit(unit) && unit.value > 0 {
+                        readings.push((unit.end, token.value + unit.value));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    readings
+}
+
+/*
+ * The longest number the parts starting at a place make up, as (end, value).
+ */
+fn longest_compound(starting: &[Vec<Match>], at: usize) -> Option<(usize, u32)> {
+    let mut readings = below_hundred(starting, at);
+    // Hundreds, of a unit or of one if none is given, and whatever below a hundred follows them
+    let mut hundreds: Vec<(usize, u32)> = Vec::new();
+    for token in &starting[at] {
+        if token.value == 100 {
+            hundreds.push((token.end, 1));
+        } else if is_unit(token) && token.value > 0 {
+            for next in starting.get(token.end).map_or(&[][..], Vec::as_slice) {
+                if next.value == 100 {
+                    hundreds.push((next.end, token.value));
+                }
+            }
+        }
+    }
+    for (end, multiple) in hundreds {
+        readings.push((end, multiple * 100));
+        for (rest_end, rest) in below_hundred(starting, end) {
+            if rest > 0 {
+                readings.push((rest_end, multiple * 100 + rest));
+            }
+        }
+    }
+    readings.into_iter().max_by_key(|&(end, _)| end)
+}
+
+/*
+ * The numbers in a line, in the order they start: every digit token (runs of digits as one number)
+ * or, with compound, every longest compound, leaving out those within another.
+ */
+pub fn numbers(matcher: &DigitMatcher, line: &[u8], compound: bool) -> Vec<Number> {
+    // The words found, by where they start; digits are taken in runs instead, and are no parts
+    let mut starting: Vec<Vec<Match>> = vec![Vec::new(); line.len()];
+    matcher.for_each_match(line, |found| {
+        if !line[found.start].is_ascii_digit() {
+            starting[found.start].push(found);
+        }
+    });
+
+    let mut numbers: Vec<Number> = Vec::new();
+    for start in 0..line.len() {
+        let candidate = if line[start].is_ascii_digit() {
+            let end = start + line[start..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+            Some((end, String::from_utf8_lossy(&line[start..end]).into_owned()))
+        } else if compound {
+            longest_compound(&starting, start).map(|(end, value)| (end, value.to_string()))
+        } else {
+            starting[start].iter().filter(|token| is_unit(token)).max_by_key(|token| token.end).map(|token| (token.end, token.value.to_string()))
+        };
+        if let Some((end, digits)) = candidate {
+            if numbers.last().is_none_or(|last| end > last.end) {
+                numbers.push(Number { start, end, digits: Cow::Owned(digits) });
+            }
+        }
+    }
+    numbers
+}
+
+/*
+ * The calibration value from the first and last numbers of a line by the rule, or None if it is
+ * too large.
+ */
+pub fn calibration_value(first: &Number, last: &Number, rule: Rule) -> Option<u64> {
+    match rule {
+        Rule::Digit => {
+            let first_digit = first.digits.chars().next().and_then(|digit| digit.to_digit(10))?;
+            let last_digit = last.digits.chars().last().and_then(|digit| digit.to_digit(10))?;
+            Some((first_digit * 10 + last_digit) as u64)
+        }
+        Rule::Number => format!("{}{}", first.digits, last.digits).parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod number_words_tests {
+    use super::*;
+    use crate::digit_matcher::DIGITS;
+
+    const ENGLISH: [(&str, u32); 10] = [
+        ("zero", 0), ("one", 1), ("two", 2), ("three", 3), ("four", 4),
+        ("five", 5), ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9),
+    ];
+
+    fn summary(line: &str, compound: bool) -> Vec<(usize, usize, String)> {
+        let tokens = [&DIGITS[..], &ENGLISH[..], &COMPOUND_WORDS[..]].concat();
+        let matcher = DigitMatcher::new(&tokens);
+        numbers(&matcher, line.as_bytes(), compound).into_iter().map(|number| (number.start, number.end, number.digits.into_owned())).collect()
+    }
+
+    fn values(line: &str, compound: bool) -> Vec<String> {
+        summary(line, compound).into_iter().map(|(_, _, digits)| digits).collect()
+    }
+
+    fn calibrate(line: &str, compound: bool, rule: Rule) -> Option<u64> {
+        let tokens = [&DIGITS[..], &ENGLISH[..], &COMPOUND_WORDS[..]].concat();
+        let numbers = numbers(&DigitMatcher::new(&tokens), line.as_bytes(), compound);
+        calibration_value(numbers.first()?, numbers.last()?, rule)
+    }
+
+    #[test]
+    fn test_compounds() {
+        assert_eq!(values("twentythree", true), vec!["23"]);
+        assert_eq!(values("xonehundredfivex", true), vec!["105"]);
+        assert_eq!(values("threehundredtwelve", true), vec!["312"]);
+        assert_eq!(values("hundredninetynine", true), vec!["199"]);
+        assert_eq!(values("ninehundred", true), vec!["900"]);
+        assert_eq!(values("seventeen", true), vec!["17"]);
+        assert_eq!(values("twentyzero", true), vec!["20", "0"], "zero only stands alone");
+        assert_eq!(values("4twenty42", true), vec!["4", "20", "42"]);
+    }
+
+    #[test]
+    fn test_overlapping_compounds() {
+        assert_eq!(summary("eighteenine", true), vec![(0, 8, "18".to_string()), (7, 11, "9".to_string())]);
+        assert_eq!(values("twentyeightwo", true), vec!["28", "2"]);
+        assert_eq!(values("seventyoneight", true), vec!["71", "8"]);
+        assert_eq!(values("eighteenine", false), vec!["8", "9"], "eighteen is not a word without compound");
+        assert_eq!(values("eightwo", true), vec!["8", "2"]);
+    }
+
+    #[test]
+    fn test_rules() {
+        assert_eq!(calibrate("eighteenine", true, Rule::Digit), Some(19));
+        assert_eq!(calibrate("eighteenine", true, Rule::Number), Some(189));
+        assert_eq!(calibrate("eighteenine", false, Rule::Digit), Some(89));
+        assert_eq!(calibrate("twentythree", false, Rule::Digit), Some(33));
+        assert_eq!(calibrate("twentythree", true, Rule::Digit), Some(23));
+        assert_eq!(calibrate("twentythree", true, Rule::Number), Some(2323));
+        assert_eq!(calibrate("a12b345c", false, Rule::Number), Some(12345));
+        assert_eq!(calibrate("a12b345c", false, Rule::Digit), Some(15));
+        assert_eq!(calibrate("nothing", true, Rule::Digit), None);
+        assert_eq!(calibrate("99999999999x99999999999", false, Rule::Number), None);
+    }
+}
@@ -0,0 +1,503 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+use common::budget::{checkpoint, Cancelled};
+use crate::digit_matcher::Match;
+use crate::line_summary::LineSummary;
+
+/*
+ * Finds the first and last digit tokens of a line without reading all of it: forwards from the
+ * start to the first token, then backwards from the end to the last. Each way is an automaton over
+ * the bytes of the tokens (read backwards, the tokens reversed), stepping once per byte read, by
+ * looking up the byte's class (bytes in no token sharing one) and then the state that follows.
+ *
+ * Nothing is allocated per line, and a whole document can be scanned in one go, without splitting
+ * it into lines first. The tokens may be any text without a newline (a token's first byte in UTF-8
+ * is never a continuation byte, so tokens are only found where characters start).
+ */
+pub struct Scanner {
+    forwards: Automaton,
+    backwards: Automaton,
+    // The length of the longest token, past which no token can start earlier than one found
+    longest: usize,
+}
+
+// Set in a step of an automaton that reaches a state where a token ends
+const FOUND: u32 = 1 << 31;
+
+/*
+ * An Aho-Corasick automaton (see DigitMatcher), as a dense table of steps by byte class.
+ */
+struct Automaton {
+    classes: [u8; 256],
+    class_count: usize,
+    // The step from each state for each class of byte, at state * class_count + class: the next
+    // state, times class_count, with FOUND set if a token ends there
+    steps: Vec<u32>,
+    // The longest token ending at each state, as (length, value)
+    tokens: Vec<(usize, u32)>,
+}
+
+impl Automaton {
+    fn new(tokens: &[(Vec<u8>, u32)]) -> Automaton {
+        let mut classes = [0; 256];
+        let mut class_count = 1;
+        for (token, _) in tokens {
+            for &byte in token {
+                if classes[byte as usize] == 0 {
+                    classes[byte as usize] = class_count as u8;
+                    class_count += 1;
+                }
+            }
+        }
+
+        // The trie of the tokens, then (breadth first, as DigitMatcher does) the step for every
+        // class that does not extend a state's token, and the longest token ending at each state
+        let mut next: Vec<Vec<Option<usize>>> = vec![vec![None; class_count]];
+        let mut ending: Vec<Option<(usize, u32)>> = vec![None];
+        for (token, value) in tokens {
+            let mut state = 0;
+            for &byte in token {
+                let class = classes[byte as usize] as usize;
+                state = match next[state][class] {
+                    Some(child) => child,
+                    None => {
+                        next.push(vec![None; class_count]);
+                        ending.push(None);
+                        next[state][class] = Some(next.len() - 1);
+                        next.len() - 1
+                    }
+                };
+            }
+            if ending[state].is_none_or(|(length, _)| length < token.len()) {
+                ending[state] = Some((token.len(), *value));
+            }
+        }
+        let mut steps = vec![0; next.len() * class_count];
+        let mut fail = vec![0; next.len()];
+        let mut queue = VecDeque::from([0]);
+        while let Some(state) = queue.pop_front() {
+            if state != 0 && ending[state].is_none() {
+                ending[state] = ending[fail[state]];
+            }
+            for class in 0..class_count {
+                let via_fail = if state == 0 { 0 } else { steps[fail[state] * class_count + class] as usize };
+                steps[state * class_count + class] = match next[state][class] {
+                    Some(child) => {
+                        fail[child] = via_fail;
+                        queue.push_back(child);
+                        child
+                    }
+                    None => via_fail,
+                } as u32;
+            }
+        }
+        for step in &mut steps {
+            let state = *step as usize;
+            *step = (state * class_count) as u32 | if ending[state].is_some() { FOUND } else { 0 };
+        }
+        let tokens = ending.into_iter().map(|found| found.unwrap_or((0, 0))).collect();
+        Automaton { classes, class_count, steps, tokens }
+    }
+
+    // The step from a state (times class_count) on reading a byte
+    fn step(&self, state: u32, byte: u8) -> u32 {
+        self.steps[(state & !FOUND) as usize + self.classes[byte as usize] as usize]
+    }
+
+    // The longest token ending at a state reached with FOUND set
+    fn token(&self, state: u32) -> (usize, u32) {
+        self.tokens[(state & !FOUND) as usize / self.class_count]
+    }
+}
+
+impl Scanner {
+    pub fn new<S: AsRef<str>>(tokens: &[(S, u32)]) -> Scanner {
+        let tokens: Vec<(Vec<u8>, u32)> = tokens.iter()
+            .map(|(token, value)| (token.as_ref().as_bytes().to_vec(), *value))
+            .filter(|(token, _)| !token.is_empty())
+            .collect();
+        let reversed: Vec<(Vec<u8>, u32)> = tokens.iter().map(|(token, value)| (token.iter().rev().copied().collect(), *value)).collect();
+        Scanner {
+            forwards: Automaton::new(&tokens),
+            backwards: Automaton::new(&reversed),
+            longest: tokens.iter().map(|(token, _)| token.len()).max().unwrap_or(0),
+        }
+    }
+
+    /*
+     * The first token of the line starting at start and ending before a newline, if there is one,
+     * and where the search stopped. A token ending at a place is the earliest starting of those
+     * ending there, so once one is found, only tokens ending within the longest token's length of
+     * its start are read past it to, for one starting earlier or as early but longer.
+     */
+    fn first_from(&self, text: &[u8], start: usize) -> (Option<Match>, usize) {
+        let mut state = 0;
+        let mut at = start;
+        for &byte in &text[start..] {
+            if byte == b'\n' {
+                break;
+            }
+            state = self.forwards.step(state, byte);
+            at += 1;
+            if state & FOUND != 0 {
+                break;
+            }
+        }
+        if state & FOUND == 0 {
+            return (None, at);
+        }
+        let (length, value) = self.forwards.token(state);
+        let mut first = Match { start: at - length, end: at, value };
+        let until = text.len().min(first.start + self.longest);
+        while at < until && text[at] != b'\n' {
+            state = self.forwards.step(state, text[at]);
+            at += 1;
+            if state & FOUND != 0 {
+                let (length, value) = self.forwards.token(state);
+                if at - length < first.start || (at - length == first.start && at > first.end) {
+                    first = Match { start: at - length, end: at, value };
+                }
+            }
+        }
+        (Some(first), at)
+    }
+
+    /*
+     * The last token of text[from..end], reading backwards from the end: the first place reached
+     * that a token starts at, the longest starting there.
+     */
+    fn last_before(&self, text: &[u8], from: usize, end: usize) -> Option<Match> {
+        let mut state = 0;
+        for start in (from..end).rev() {
+            state = self.backwards.step(state, text[start]);
+            if state & FOUND != 0 {
+                let (length, value) = self.backwards.token(state);
+                return Some(Match { start, end: start + length, value });
+            }
+        }
+        None
+    }
+
+    /*
+     * The first and last tokens in the line, or None if it has none. Of tokens starting at the
+     * same place, the longest is taken, either way (as DigitMatcher::first_and_last does).
+     */
+    pub fn first_and_last(&self, line: &[u8]) -> Option<(Match, Match)> {
+        let first = self.first_from(line, 0).0?;
+        let last = self.last_before(line, first.start, line.len());
+        Some((first, last.expect("the first token is found backwards at the latest")))
+    }
+
+    /*
+     * Calls found with the span of every line of a document (without its newline, or a '\r'
+     * before it, as common::input::lines splits them) and its first and last tokens, if it has any,
+     * with spans within the document. Each line is searched forwards to its first token, then its
+     * end is found, and it is searched backwards from there to its last.
+     */
+    pub fn for_each_line<F: FnMut(Range<usize>, Option<(Match, Match)>)>(&self, text: &[u8], mut found: F) {
+        let mut start = 0;
+        while start < text.len() {
+            let (first, at) = self.first_from(text, start);
+            let end = at + text[at..].iter().position(|&byte| byte == b'\n').unwrap_or(text.len() - at);
+            let tokens = first.map(|first| {
+                (first, self.last_before(text, first.start, end).expect("the first token is found backwards at the latest"))
+            });
+            let line_end = if end > start && text[end - 1] == b'\r' { end - 1 } else { end };
+            found(start..line_end, tokens);
+            start = end + 1;
+        }
+    }
+}
+
+// Words read between checks of the run's budget, a megabyte
+const WORDS_PER_CHECK: usize = 1 << 17;
+
+const LOW: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+const HIGH: u64 = !LOW;
+
+/*
+ * The places of the digits and of the newlines in a word, as the high bits of their bytes.
+ */
+fn digit_and_newline_places(bytes: u64) -> (u64, u64) {
+    let repeat = |byte: u8| u64::from_ne_bytes([byte; 8]);
+    // Each byte's low seven bits are added to without carrying into the next byte, so the high
+    // bits say: below 10 after '0' is taken away (a digit), and zero after '\n' is (a newline)
+    let digits = bytes ^ repeat(b'0');
+    let digits = !(((digits & LOW) + repeat(0x80 - 10)) | digits) & HIGH;
+    let newlines = bytes ^ repeat(b'\n');
+    let newlines = !(((newlines & LOW) + LOW) | newlines) & HIGH;
+    (digits, newlines)
+}
+
+/*
+ * Sums the values of a document's lines as part 1 reads them, taking only the ASCII digits 0 to 9,
+ * as a Scanner of them would. Rather than looking at each byte in turn, the document is read eight
+ * bytes at a time, into a word with the high bit set of each byte that is a digit or a newline, and
+ * only those bytes are visited, with nothing to decide but which of them each is.
+ *
+ * Given a summary, the lines with fewer than two digits are noted in it too, which takes about
+ * twice as long where most lines are such lines. Fails if the run's budget runs out, checked every
+ * megabyte.
+ */
+pub fn sum_ascii_digit_lines(text: &[u8], summary: Option<&mut LineSummary>) -> Result<u64, Cancelled> {
+    match summary {
+        Some(summary) => sum_and_summarise_ascii_digit_lines(text, summary),
+        None => sum_only_ascii_digit_lines(text),
+    }
+}
+
+fn sum_only_ascii_digit_lines(text: &[u8]) -> Result<u64, Cancelled> {
+    let mut total = 0;
+    // Whether the line so far has a digit, and if so its first and last
+    let (mut found, mut first, mut last) = (0, 0, 0);
+    let mut visit = |byte: u8| {
+        let newline = (byte == b'\n') as u64;
+        total += newline * found * (first * 10 + last);
+        let digit = byte.wrapping_sub(b'0') as u64;
+        first = if found == 1 { first } else { digit };
+        last = digit;
+        found = 1 - newline;
+    };
+
+    let mut words = text.chunks_exact(8);
+    for (i, word) in (&mut words).enumerate() {
+        if i % WORDS_PER_CHECK == 0 {
+            checkpoint()?;
+        }
+        let (digits, newlines) = digit_and_newline_places(u64::from_le_bytes(word.try_into().expect("words are eight bytes")));
+        let mut places = digits | newlines;
+        while places != 0 {
+            visit(word[places.trailing_zeros() as usize / 8]);
+            places &= places - 1;
+        }
+    }
+    for &byte in words.remainder() {
+        if byte.is_ascii_digit() || byte == b'\n' {
+            visit(byte);
+        }
+    }
+    visit(b'\n');
+    Ok(total)
+}
+
+/*
+ * As sum_only_ascii_digit_lines, in arithmetic rather than in branches to mispredict, noting the
+ * lines with fewer than two digits in the summary. Most are only counted, from bits set at their
+ * newlines; the rest, the first few of each kind and those that may be blank (starting with
+ * anything other than a visible ASCII character), are noted one by one after their word.
+ */
+fn sum_and_summarise_ascii_digit_lines(text: &[u8], summary: &mut LineSummary) -> Result<u64, Cancelled> {
+    // The bytes after the last whole word, padded with zeros (neither digits nor newlines), and a
+    // newline ending the last line if it has none, are the last word
+    let words = text.chunks_exact(8);
+    let remainder = words.remainder();
+    let mut tail = [0; 8];
+    tail[..remainder.len()].copy_from_slice(remainder);
+    if text.last().is_some_and(|&byte| byte != b'\n') {
+        tail[remainder.len()] = b'\n';
+    }
+
+    let mut total = 0;
+    // The digits in the line so far (counting no further than two), the first and last of them,
+    // and the line's number (counted a word at a time) and where it starts
+    let (mut digits, mut first, mut last) = (0, 0, 0);
+    let (mut line_number, mut line_start) = (1, 0);
+    // The lines only counted, and whether lines with no digit and with one are still listed
+    let (mut no_digit, mut one_digit) = (0, 0);
+    let mut listing = [summary.listing(0), summary.listing(1)];
+
+    for (i, word) in words.chain([&tail[..]]).enumerate() {
+        if i % WORDS_PER_CHECK == 0 {
+            checkpoint()?;
+        }
+        let (digit_places, newline_places) = digit_and_newline_places(u64::from_le_bytes(word.try_into().expect("words are eight bytes")));
+
+        // The lines ending in the word with no digit and with one, and those to note one by one, as
+        // bits at their newlines
+        let (mut none, mut one, mut to_note) = (0, 0, 0);
+        let (word_line_number, word_line_start) = (line_number, line_start);
+        let mut places = digit_places | newline_places;
+        while places != 0 {
+            let bit = places.trailing_zeros();
+            let byte = word[bit as usize / 8];
+            let newline = (byte == b'\n') as u64;
+            total += newline * digits.min(1) * (first * 10 + last);
+            let (ends_none, ends_one) = (newline & (digits == 0) as u64, newline & (digits == 1) as u64);
+            let maybe_blank = !text[line_start].is_ascii_graphic() as u64;
+            none |= ends_none << bit;
+            one |= ends_one << bit;
+            to_note |= (ends_none & (listing[0] as u64 | maybe_blank) | ends_one & listing[1] as u64) << bit;
+            let digit = byte.wrapping_sub(b'0') as u64;
+            first = if digits > 0 { first } else { digit };
+            last = digit;
+            digits = (1 - newline) * (digits.min(1) + 1);
+            line_start = if newline == 1 { i * 8 + bit as usize / 8 + 1 } else { line_start };
+            places &= places - 1;
+        }
+        line_number += newline_places.count_ones() as usize;
+
+        if to_note != 0 {
+            let mut marked = to_note;
+            while marked != 0 {
+                let bit = marked.trailing_zeros();
+                // The line starts after the newline before it in the word, if there is one
+                let before = newline_places & ((1 << bit) - 1);
+                let start = if before == 0 { word_line_start } else { i * 8 + (63 - before.leading_zeros() as usize) / 8 + 1 };
+                let line = &text[start..i * 8 + bit as usize / 8];
+                let line_digits = ((one >> bit) & 1) as usize;
+                summary.note(word_line_number + before.count_ones() as usize, line.strip_suffix(b"\r").unwrap_or(line), line_digits);
+                marked &= marked - 1;
+            }
+            listing = [summary.listing(0), summary.listing(1)];
+        }
+        no_digit += (none & !to_note).count_ones() as usize;
+        one_digit += (one & !to_note).count_ones() as usize;
+    }
+
+    summary.no_digit.count += no_digit;
+    summary.one_digit.count += one_digit;
+    Ok(total)
+}
+
+#[cfg(test)]
+mod scanner_tests {
+    use super::*;
+    use crate::digit_matcher::{DigitMatcher, DIGITS};
+    use crate::line_summary::LineCheck;
+    use crate::{calibration_value_collected, sum_calibration_document, Mode};
+    use crate::vocabulary;
+    use common::random::Rng;
+    use std::ops::Range;
+
+    fn spans(scanner: &Scanner, line: &str) -> Option<(Range<usize>, Range<usize>)> {
+        scanner.first_and_last(line.as_bytes()).map(|(first, last)| (first.start..first.end, last.start..last.end))
+    }
+
+    #[test]
+    fn test_first_and_last() {
+        let scanner = Scanner::new(&[&DIGITS[..], &vocabulary::ENGLISH[..]].concat());
+        assert_eq!(spans(&scanner, "eightwothree"), Some((0..5, 7..12)));
+        assert_eq!(spans(&scanner, "twone"), Some((0..3, 2..5)));
+        assert_eq!(spans(&scanner, "treb7uchet"), Some((4..5, 4..5)));
+        assert_eq!(spans(&scanner, "xtwone3fou"), Some((1..4, 6..7)));
+        assert_eq!(spans(&scanner, "trebuchet"), None);
+        assert_eq!(spans(&scanner, ""), None);
+    }
+
+    #[test]
+    fn test_longer_token_preferred() {
+        let scanner = Scanner::new(&[("seven", 7), ("seventeen", 17), ("teen", 10)]);
+        let (first, last) = scanner.first_and_last(b"xseventeen").unwrap();
+        assert_eq!((first.value, last.value), (17, 10));
+        assert_eq!(spans(&scanner, "xseventeen"), Some((1..10, 6..10)));
+    }
+
+    #[test]
+    fn test_multibyte_tokens() {
+        let scanner = Scanner::new(&[&DIGITS[..], &vocabulary::FRENCH[..], &[("٣", 3)][..]].concat());
+        let (first, last) = scanner.first_and_last("zéro9٣x".as_bytes()).unwrap();
+        assert_eq!((first.value, last.value), (0, 3));
+        assert_eq!(spans(&scanner, "zéro9٣x"), Some((0..5, 6..8)));
+        assert_eq!(spans(&scanner, "é"), None);
+    }
+
+    #[test]
+    fn test_for_each_line() {
+        let scanner = Scanner::new(&[&DIGITS[..], &vocabulary::ENGLISH[..]].concat());
+        let text = "two1nine\nabc\r\n\nx7y\nlast3";
+        let mut lines = Vec::new();
+        scanner.for_each_line(text.as_bytes(), |line, tokens| {
+            lines.push((line, tokens.map(|(first, last)| (first.start..first.end, last.start..last.end))));
+        });
+        assert_eq!(lines, vec![
+            (0..8, Some((0..3, 4..8))),
+            (9..12, None),
+            (14..14, None),
+            (15..18, Some((16..17, 16..17))),
+            (19..24, Some((23..24, 23..24))),
+        ]);
+    }
+
+    #[test]
+    fn test_sum_ascii_digit_lines() {
+        let scanner = Scanner::new(&DIGITS);
+        let document = crate::generate_document(&mut Rng::new(7), 500);
+        let blanks = "\n".repeat(30) + "1\n\r\n \n\u{3000}\n\u{b}x\n";
+        let texts = [&document[..], &blanks, "", "\n\n", "1", "a1b2\r\n٣x9\n\nno digits\n09", "x0y\n0123456789abcdef\n", "\r\nab\r"];
+        for text in texts.into_iter().chain(document.lines()) {
+            let (mut sum, mut line_number, mut summary) = (0, 0, LineSummary::default());
+            scanner.for_each_line(text.as_bytes(), |line, tokens| {
+                line_number += 1;
+                sum += tokens.map_or(0, |(first, last)| (first.value * 10 + last.value) as u64);
+                summary.note(line_number, &text.as_bytes()[line], tokens.map_or(0, |(first, last)| if first == last { 1 } else { 2 }));
+            });
+            assert_eq!(sum_ascii_digit_lines(text.as_bytes(), None).unwrap(), sum, "{:?}", text);
+            let mut found = LineSummary::default();
+            assert_eq!(sum_ascii_digit_lines(text.as_bytes(), Some(&mut found)).unwrap(), sum, "{:?}", text);
+            assert_eq!(found, summary, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_agrees_with_matcher() {
+        let tokens = [&DIGITS[..], &vocabulary::ENGLISH[..]].concat();
+        let (scanner, matcher) = (Scanner::new(&tokens), DigitMatcher::new(&tokens));
+        for line in ["oneighthreeight", "7pqrstsixteen", "nineight", "zoneight234", "eeeeight", "abc"] {
+            assert_eq!(scanner.first_and_last(line.as_bytes()), matcher.first_and_last(line.as_bytes()), "{}", line);
+        }
+    }
+
+    // A document of random lines from digits, letters of the number words, spaces, '\r' and
+    // multibyte characters, with lengths around multiples of eight, so lines, digits and words
+    // fall either side of the words sum_ascii_digit_lines reads
+    fn random_document(rng: &mut Rng, lines: usize) -> String {
+        let alphabet = ["1", "7", "0", "o", "n", "e", "t", "w", "h", "r", "i", "g", "s", "v", "x", " ", "\r", "é", "٣", "\u{3000}"];
+        let mut document = String::new();
+        for _ in 0..lines {
+            let length = (rng.range(0..4) * 8 + rng.range(0..3)).saturating_sub(1);
+            for _ in 0..length {
+                document.push_str(alphabet[rng.range(0..alphabet.len() as u64) as usize]);
+            }
+            if !rng.one_in(20) {
+                document.push('\n');
+            }
+        }
+        document
+    }
+
+    #[test]
+    fn test_random_documents() {
+        let (part_1, part_2) = (Mode::part(1), Mode::part(2));
+        let tokens = [&DIGITS[..], &vocabulary::ENGLISH[..]].concat();
+        let (scanner, matcher) = (Scanner::new(&tokens), DigitMatcher::new(&tokens));
+        let mut rng = Rng::new(48);
+        for lines in (0..200).map(|i| i % 12) {
+            let document = random_document(&mut rng, lines as usize);
+            let sum: u64 = document.split('\n').map(|line| {
+                calibration_value_collected(line.strip_suffix('\r').unwrap_or(line), &part_1).unwrap().unwrap_or(0)
+            }).sum();
+            assert_eq!(sum_ascii_digit_lines(document.as_bytes(), None).unwrap(), sum, "{:?}", document);
+            assert_eq!(sum_ascii_digit_lines(document.as_bytes(), Some(&mut LineSummary::default())).unwrap(), sum, "{:?}", document);
+
+            let mut found = Vec::new();
+            scanner.for_each_line(document.as_bytes(), |line, tokens| found.push((line, tokens)));
+            let mut start = 0;
+            let expected: Vec<_> = document.split('\n').map(|line| {
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                let shift = |found: Match| Match { start: found.start + start, end: found.end + start, ..found };
+                let tokens = matcher.first_and_last(line.as_bytes()).map(|(first, last)| (shift(first), shift(last)));
+                assert_eq!(scanner.first_and_last(line.as_bytes()), matcher.first_and_last(line.as_bytes()), "{:?}", line);
+                let span = start..start + line.len();
+                start += document[start..].find('\n').map_or(0, |end| end + 1);
+                (span, tokens)
+            }).collect();
+            // A final newline ends the last line rather than starting another
+            let expected = if document.ends_with('\n') || document.is_empty() { &expected[..expected.len() - 1] } else { &expected[..] };
+            assert_eq!(found, expected, "{:?}", document);
+            let words: u64 = document.split('\n').map(|line| {
+                calibration_value_collected(line.strip_suffix('\r').unwrap_or(line), &part_2).unwrap().unwrap_or(0)
+            }).sum();
+            assert_eq!(sum_calibration_document(document.as_bytes(), &part_2, LineCheck::Off).unwrap().answer, words.to_string());
+        }
+    }
+}
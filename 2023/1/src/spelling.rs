@@ -0,0 +1,244 @@
+use std::borrow::Cow;
+use crate::digit_matcher::DIGITS;
+use crate::number_words::Number;
+
+/*
+ * How far a spelled-out digit may stray from its spelling in the vocabulary and still be read: in
+ * another case, and by up to a number of edits (see Fuzzy). Text from OCR has "Seven", "SEVEN" and
+ * "sevn" for "seven".
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Spelling {
+    pub ignore_case: bool,
+    pub max_edits: usize,
+}
+
+/*
+ * The line with each character in lower case, where that is a single character of the same length
+ * in UTF-8 (as it is for the letters of every built-in vocabulary), so that the spans of tokens
+ * found in it are their spans in the line too.
+ */
+pub fn fold_case(line: &str) -> Cow<'_, str> {
+    if line.is_ascii() {
+        return if line.bytes().any(|byte| byte.is_ascii_uppercase()) { Cow::Owned(line.to_ascii_lowercase()) } else { Cow::Borrowed(line) };
+    }
+    Cow::Owned(line.chars().map(|c| {
+        let mut lower = c.to_lowercase();
+        match (lower.next(), lower.next()) {
+            (Some(lower), None) if lower.len_utf8() == c.len_utf8() => lower,
+            _ => c,
+        }
+    }).collect())
+}
+
+/*
+ * The edits that make one word another: a character inserted, deleted or replaced, or two
+ * neighbours swapped ("nien" is one edit from "nine"), each edited at most once (the optimal string
+ * alignment distance).
+ */
+fn edits(from: &[char], to: &[char]) -> usize {
+    // distances[i][j] is the edits from the first i characters of from to the first j of to
+    let mut distances = vec![vec![0; to.len() + 1]; from.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=from.len() {
+        for j in 1..=to.len() {
+            let replaced = distances[i - 1][j - 1] + (from[i - 1] != to[j - 1]) as usize;
+            let mut distance = replaced.min(distances[i - 1][j] + 1).min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && from[i - 1] == to[j - 2] && from[i - 2] == to[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[from.len()][to.len()]
+}
+
+/*
+ * A misspelling read as a word of the vocabulary: its span in the line, the word and its digit, and
+ * the edits between them.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub start: usize,
+    pub end: usize,
+    pub word: String,
+    pub value: u32,
+    pub edits: usize,
+}
+
+impl FuzzyMatch {
+    /*
+     * How sure the reading is: 1 for the word itself, less each edit's share of its letters.
+     */
+    pub fn confidence(&self) -> f64 {
+        1.0 - self.edits as f64 / self.word.chars().count() as f64
+    }
+
+    /*
+     * Describes the match, e.g. "sevn at 3..7 read as seven (7), 1 edit, confidence 0.80".
+     */
+    pub fn describe(&self, line: &str) -> String {
+        format!(
+            "{} at {}..{} read as {} ({}), {} edit{}, confidence {:.2}",
+            &line[self.start..self.end], self.start, self.end, self.word, self.value,
+            self.edits, if self.edits == 1 { "" } else { "s" }, self.confidence(),
+        )
+    }
+}
+
+/*
+ * Finds the words of a vocabulary misspelt in a line. A word of n letters may be read with up to
+ * (n - 1) / 3 edits, and never more than the most given: so "sevn" and "nien" are read (one edit
+ * from five letters and four), but nothing is read as "one" or "six" other than themselves, as
+ * almost any three letters are an edit or two from one of them.
+ *
+ * Only runs of letters are read, as digits are digits. Where letters are as close to words with
+ * different digits ("fine" to "five" and "nine"), nothing is read there, nor inside them.
+ */
+pub struct Fuzzy {
+    // Each word as characters, with the word, its digit and the edits it may be read with
+    words: Vec<(Vec<char>, String, u32, usize)>,
+}
+
+impl Fuzzy {
+    pub fn new(vocabulary: &[(String, u32)], max_edits: usize) -> Fuzzy {
+        let words = vocabulary.iter().map(|(word, value)| {
+            let chars: Vec<char> = word.chars().collect();
+            let allowed = max_edits.min(chars.len().saturating_sub(1) / 3);
+            (chars, word.clone(), *value, allowed)
+        }).collect();
+        Fuzzy { words }
+    }
+
+    /*
+     * The misspelt words in a line, by where they start: at each place, the reading with the
+     * fewest edits, and of those, the one closest to its word's length. Places a word is spelled
+     * correctly at are left to the exact matches.
+     */
+    pub fn matches(&self, line: &str) -> Vec<FuzzyMatch> {
+        let (offsets, chars): (Vec<usize>, Vec<char>) = line.char_indices().unzip();
+        let mut found = Vec::new();
+        let mut ambiguous_spans = Vec::new();
+        for start in 0..chars.len() {
+            let letters = chars[start..].iter().take_while(|c| c.is_alphabetic()).count();
+            // The best reading so far, as (edits, difference in length, length, word), and
+            // whether another word's reading is as good
+            let mut best: Option<(usize, usize, usize, usize)> = None;
+            let mut ambiguous = false;
+            for (i, (word, _, value, allowed)) in self.words.iter().enumerate() {
+                let shortest = word.len().saturating_sub(*allowed).max(1);
+                for length in shortest..=(word.len() + allowed).min(letters) {
+                    let edits = edits(&chars[start..start + length], word);
+                    if edits > *allowed {
+                        continue;
+                    }
+                    let reading = (edits, length.abs_diff(word.len()), length, i);
+                    match best {
+                        Some(best) if (best.0, best.1) < (reading.0, reading.1) => {}
+                        Some(best) if (best.0, best.1) == (reading.0, reading.1) => {
+                            ambiguous |= self.words[best.3].2 != *value;
+                        }
+                        _ => {
+                            best = Some(reading);
+                            ambiguous = false;
+                        }
+                    }
+                }
+            }
+            if let Some((edits, _, length, i)) = best {
+                let end = offsets.get(start + length).copied().unwrap_or(line.len());
+                if ambiguous {
+                    ambiguous_spans.push(offsets[start]..end);
+                } else if edits > 0 {
+                    let (_, word, value, _) = &self.words[i];
+                    found.push(FuzzyMatch { start: offsets[start], end, word: word.clone(), value: *value, edits });
+                }
+            }
+        }
+        found.retain(|fuzzy| !ambiguous_spans.iter().any(|span| span.start <= fuzzy.start && fuzzy.end <= span.end));
+        found
+    }
+}
+
+/*
+ * Adds fuzzy matches to the numbers found exactly, keeping them in order of where they start, but
+ * for those inside a number or inside another match with no more edits (the "even" of "seven", or
+ * the "nin" of "nine"). Returns the matches added.
+ */
+pub fn merge(numbers: &mut Vec<Number>, found: Vec<FuzzyMatch>) -> Vec<FuzzyMatch> {
+    let inside = |inner: &FuzzyMatch, start: usize, end: usize| start <= inner.start && inner.end <= end;
+    let kept: Vec<FuzzyMatch> = found.iter().filter(|fuzzy| {
+        !numbers.iter().any(|number| inside(fuzzy, number.start, number.end))
+            && !found.iter().any(|other| other != *fuzzy && other.edits <= fuzzy.edits && inside(fuzzy, other.start, other.end))
+    }).cloned().collect();
+    numbers.extend(kept.iter().map(|fuzzy| Number { start: fuzzy.start, end: fuzzy.end, digits: Cow::Borrowed(DIGITS[fuzzy.value as usize].0) }));
+    numbers.sort_by_key(|number| number.start);
+    kept
+}
+
+#[cfg(test)]
+mod spelling_tests {
+    use super::*;
+    use crate::vocabulary;
+
+    fn english(max_edits: usize) -> Fuzzy {
+        let words: Vec<(String, u32)> = vocabulary::ENGLISH.iter().map(|&(word, value)| (word.to_string(), value)).collect();
+        Fuzzy::new(&words, max_edits)
+    }
+
+    fn read(fuzzy: &Fuzzy, line: &str) -> Vec<(String, u32, usize)> {
+        fuzzy.matches(line).into_iter().map(|found| (line[found.start..found.end].to_string(), found.value, found.edits)).collect()
+    }
+
+    #[test]
+    fn test_fold_case() {
+        assert_eq!(fold_case("seven"), Cow::Borrowed("seven"));
+        assert_eq!(fold_case("SEVEN2Nine"), "seven2nine");
+        assert_eq!(fold_case("FÜNFZÉRO"), "fünfzéro");
+        // The lower case of İ is two characters, and of Ⱥ shorter in UTF-8, so both are kept
+        assert_eq!(fold_case("İⱥȺ"), "İⱥȺ");
+    }
+
+    #[test]
+    fn test_edits() {
+        let chars = |word: &str| word.chars().collect::<Vec<char>>();
+        assert_eq!(edits(&chars("sevn"), &chars("seven")), 1);
+        assert_eq!(edits(&chars("nien"), &chars("nine")), 1);
+        assert_eq!(edits(&chars("eihgt"), &chars("eight")), 1);
+        assert_eq!(edits(&chars("tree"), &chars("three")), 1);
+        assert_eq!(edits(&chars("fuor"), &chars("five")), 3);
+        assert_eq!(edits(&chars(""), &chars("two")), 3);
+    }
+
+    #[test]
+    fn test_matches() {
+        let fuzzy = english(1);
+        assert_eq!(read(&fuzzy, "xsevny"), vec![("sevn".to_string(), 7, 1)]);
+        assert_eq!(read(&fuzzy, "nien"), vec![("nien".to_string(), 9, 1)]);
+        // The words as spelled, and three letters, are not read
+        assert_eq!(read(&fuzzy, "seven"), vec![("even".to_string(), 7, 1)]);
+        assert_eq!(read(&fuzzy, "on tw sx"), vec![]);
+        // fine is one edit from both five and nine
+        assert_eq!(read(&fuzzy, "fine"), vec![]);
+        assert_eq!(read(&fuzzy, "fine sevn"), vec![("sevn".to_string(), 7, 1)]);
+        assert_eq!(read(&english(0), "sevn nien"), vec![]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let line = "sevenxnien4";
+        let mut numbers = vec![
+            Number { start: 0, end: 5, digits: Cow::Borrowed("7") },
+            Number { start: 10, end: 11, digits: Cow::Borrowed("4") },
+        ];
+        let kept = merge(&mut numbers, english(1).matches(line));
+        assert_eq!(kept.iter().map(|found| found.describe(line)).collect::<Vec<_>>(), vec!["nien at 6..10 read as nine (9), 1 edit, confidence 0.75"]);
+        let spans: Vec<(usize, usize, &str)> = numbers.iter().map(|number| (number.start, number.end, &number.digits[..])).collect();
+        assert_eq!(spans, vec![(0, 5, "7"), (6, 10, "9"), (10, 11, "4")]);
+    }
+}
@@ -0,0 +1,147 @@
+use std::io::{self, IsTerminal};
+use std::env;
+use std::process;
+use std::str;
+use common::input::MappedInput;
+use common::solver::SolveError;
+use day1::line_summary::{LineCheck, LineSummary};
+use day1::number_words::Rule;
+use day1::spelling::Spelling;
+use day1::unicode_digits::{self, DigitPolicy};
+use day1::{explain, vocabulary, Mode};
+
+const USAGE: &str = "usage: sum_calibration_document FILE [--part 1|2] [--digits ascii|unicode] [--explain | --quiet] [--strict]
+    [--vocabulary NAME|PATH]... [--compound] [--rule digit|number] [--ignore-case] [--fuzzy EDITS]
+--part 2 also reads spelled-out digits; the options on the second line are for part 2 only
+--digits unicode counts every Unicode decimal digit, such as ٣, rather than only 0 to 9
+--explain shows each line with the spans its value came from highlighted, rather than describing them
+--quiet prints only the sum (and any numeric characters ignored), reading the document in one pass
+--strict fails on the first line with no digit or blank, rather than only summarising such lines
+--vocabulary chooses the spelled-out digits: english (the default), french, german, spanish, or a file
+--compound also reads English number words such as twentythree or onehundredfive as numbers
+--rule number takes the whole first and last numbers rather than their first and last digits
+--ignore-case reads spelled-out digits in any case, such as Seven or SEVEN
+--fuzzy reads misspelt words up to EDITS edits from the vocabulary's (one for words of 4 letters or
+    more, two for 7 or more), such as sevn or nien, reporting each with a confidence";
+
+/*
+ * Reports what is wrong with the document, such as a line with no digit under --strict, and exits.
+ */
+fn document_error<T>(error: SolveError) -> T {
+    eprintln!("{}", error);
+    process::exit(1);
+}
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().collect();
+    let usage_error = || io::Error::new(io::ErrorKind::InvalidInput, USAGE);
+    let mut file_path = None;
+    let mut part = 1;
+    let mut policy = DigitPolicy::Ascii;
+    let mut explain = false;
+    let mut quiet = false;
+    let mut strict = false;
+    let mut vocabularies = Vec::new();
+    let mut compound = false;
+    let mut rule = None;
+    let mut spelling = Spelling::default();
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--part" => part = match rest.next().map(String::as_str) {
+                Some("1") => 1,
+                Some("2") => 2,
+                _ => return Err(usage_error()),
+            },
+            "--digits" => policy = match rest.next().map(String::as_str) {
+                Some("ascii") => DigitPolicy::Ascii,
+                Some("unicode") => DigitPolicy::Unicode,
+                _ => return Err(usage_error()),
+            },
+            "--explain" => explain = true,
+            "--quiet" => quiet = true,
+            "--strict" => strict = true,
+            "--vocabulary" => vocabularies.push(vocabulary::load(rest.next().ok_or_else(usage_error)?)?),
+            "--compound" => compound = true,
+            "--rule" => rule = match rest.next().map(String::as_str) {
+                Some("digit") => Some(Rule::Digit),
+                Some("number") => Some(Rule::Number),
+                _ => return Err(usage_error()),
+            },
+            "--ignore-case" => spelling.ignore_case = true,
+            "--fuzzy" => spelling.max_edits = rest.next().and_then(|edits| edits.parse().ok()).ok_or_else(usage_error)?,
+            _ if file_path.is_none() && !arg.starts_with("--") => file_path = Some(arg),
+            _ => return Err(usage_error()),
+        }
+    }
+    if explain && quiet {
+        return Err(usage_error());
+    }
+    let input = MappedInput::open(file_path.ok_or_else(usage_error)?)?;
+    let mode = if part == 1 {
+        if !vocabularies.is_empty() || compound || rule.is_some() || spelling != Spelling::default() {
+            return Err(usage_error());
+        }
+        Mode::digits(policy)
+    } else {
+        if vocabularies.is_empty() {
+            vocabularies.push(vocabulary::load("english")?);
+        }
+        Mode::words(policy, vocabulary::combine(vocabularies)?, compound, rule.unwrap_or(Rule::Digit), spelling)
+    };
+
+    if quiet {
+        // The lines are only summarised when they are checked, as that slows reading them
+        let check = if strict { LineCheck::Strict } else { LineCheck::Off };
+        let solution = day1::sum_calibration_document(input.bytes(), &mode, check).unwrap_or_else(document_error);
+        for diagnostic in &solution.diagnostics {
+            eprintln!("{}", diagnostic);
+        }
+        println!("Sum: {}", solution.answer);
+        return Ok(());
+    }
+
+    let colour = io::stdout().is_terminal();
+
+    let mut sum: u64 = 0;
+    let mut summary = LineSummary::default();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = str::from_utf8(line).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let line = line.trim();
+        if !explain {
+            println!("{}", line);
+        }
+        for (at, c) in policy.non_digits(line) {
+            eprintln!("line {}: {}, ignored", i + 1, unicode_digits::describe_non_digit(at, c));
+        }
+
+        let calibration = day1::calibration_value(line, &mode).unwrap_or_else(document_error);
+        summary.note_calibration(i + 1, line.as_bytes(), calibration.as_ref());
+        if strict {
+            summary.check_strict().unwrap_or_else(document_error);
+        }
+        if let Some(calibration) = calibration {
+            if explain {
+                let (first, last) = (&calibration.first, &calibration.last);
+                println!("{}", explain::highlight(line, first.start..first.end, last.start..last.end, colour));
+            } else {
+                println!("{}", day1::describe(line, &calibration));
+            }
+            for fuzzy in &calibration.fuzzy {
+                println!("fuzzy: {}", fuzzy.describe(line));
+            }
+            println!("{}", calibration.value);
+            sum = sum.checked_add(calibration.value).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "overflow while summing calibration values")
+            })?;
+        }
+    }
+
+    for line in summary.describe() {
+        eprintln!("{}", line);
+    }
+    println!("Sum: {}", sum);
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+/*
+ * Which characters count as digits in a calibration document.
+ *
+ * By default only the ASCII digits do. With the Unicode policy, so do all the decimal digits of
+ * Unicode (general category Nd), with their own values: Arabic-Indic ٣ is 3, fullwidth ７ is 7.
+ * Other numeric characters, such as ½ or Ⅻ, are never digits, under either policy, and are
+ * reported rather than being given a value.
+ *
+ * The decimal digits come in blocks of ten from 0 to 9, so the table below holds the zero of each
+ * block, as of Unicode 14.0.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitPolicy {
+    Ascii,
+    Unicode,
+}
+
+pub const ZEROS: [char; 66] = [
+    '\u{30}', '\u{660}', '\u{6f0}', '\u{7c0}', '\u{966}', '\u{9e6}', '\u{a66}', '\u{ae6}',
+    '\u{b66}', '\u{be6}', '\u{c66}', '\u{ce6}', '\u{d66}', '\u{de6}', '\u{e50}', '\u{ed0}',
+    '\u{f20}', '\u{1040}', '\u{1090}', '\u{17e0}', '\u{1810}', '\u{1946}', '\u{19d0}', '\u{1a80}',
+    '\u{1a90}', '\u{1b50}', '\u{1bb0}', '\u{1c40}', '\u{1c50}', '\u{a620}', '\u{a8d0}', '\u{a900}',
+    '\u{a9d0}', '\u{a9f0}', '\u{aa50}', '\u{abf0}', '\u{ff10}', '\u{104a0}', '\u{10d30}', '\u{11066}',
+    '\u{110f0}', '\u{11136}', '\u{111d0}', '\u{112f0}', '\u{11450}', '\u{114d0}', '\u{11650}', '\u{116c0}',
+    '\u{11730}', '\u{118e0}', '\u{11950}', '\u{11c50}', '\u{11d50}', '\u{11da0}', '\u{16a60}', '\u{16ac0}',
+    '\u{16b50}', '\u{1d7ce}', '\u{1d7d8}', '\u{1d7e2}', '\u{1d7ec}', '\u{1d7f6}', '\u{1e140}', '\u{1e2f0}',
+    '\u{1e950}', '\u{1fbf0}',
+];
+
+impl DigitPolicy {
+    /*
+     * The value of a character that is a digit under the policy.
+     */
+    pub fn digit_value(self, c: char) -> Option<u32> {
+        match self {
+            DigitPolicy::Ascii => c.to_digit(10),
+            DigitPolicy::Unicode => {
+                // The last block whose zero is not after the character is the only one it can be in
+                let i = ZEROS.partition_point(|&zero| zero <= c).checked_sub(1)?;
+                let value = c as u32 - ZEROS[i] as u32;
+                if value < 10 {
+                    Some(value)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /*
+     * The digits under the policy other than the ASCII ones (which a DigitMatcher is always given),
+     * as (token, value).
+     */
+    pub fn non_ascii_tokens(self) -> Vec<(String, u32)> {
+        let zeros = match self {
+            DigitPolicy::Ascii => &[][..],
+            DigitPolicy::Unicode => &ZEROS[1..],
+        };
+        zeros.iter()
+            .flat_map(|&zero| (0..10).map(move |value| (char::from_u32(zero as u32 + value), value)))
+            .map(|(digit, value)| (digit.expect("digits are characters").to_string(), value))
+            .collect()
+    }
+
+    /*
+     * The numeric characters in a line that are not digits under the policy, with where they are.
+     */
+    pub fn non_digits(self, line: &str) -> Vec<(usize, char)> {
+        if line.is_ascii() {
+            return Vec::new();
+        }
+        line.char_indices().filter(|&(_, c)| c.is_numeric() && self.digit_value(c).is_none()).collect()
+    }
+}
+
+/*
+ * Describes a numeric character that is not a digit, e.g. "½ at 4 is numeric but not a decimal
+ * digit".
+ */
+pub fn describe_non_digit(at: usize, c: char) -> String {
+    if DigitPolicy::Unicode.digit_value(c).is_some() {
+        format!("{} at {} is a decimal digit but not ASCII (see --digits unicode)", c, at)
+    } else {
+        format!("{} at {} is numeric but not a decimal digit", c, at)
+    }
+}
+
+#[cfg(test)]
+mod unicode_digits_tests {
+    use super::*;
+
+    #[test]
+    fn test_digit_values() {
+        assert_eq!(DigitPolicy::Unicode.digit_value('7'), Some(7));
+        assert_eq!(DigitPolicy::Unicode.digit_value('٣'), Some(3));
+        assert_eq!(DigitPolicy::Unicode.digit_value('７'), Some(7));
+        assert_eq!(DigitPolicy::Unicode.digit_value('𝟗'), Some(9));
+        assert_eq!(DigitPolicy::Unicode.digit_value('½'), None);
+        assert_eq!(DigitPolicy::Unicode.digit_value('Ⅻ'), None);
+        assert_eq!(DigitPolicy::Unicode.digit_value('a'), None);
+        assert_eq!(DigitPolicy::Unicode.digit_value('\u{10}'), None);
+        assert_eq!(DigitPolicy::Ascii.digit_value('٣'), None);
+        assert_eq!(DigitPolicy::Ascii.digit_value('3'), Some(3));
+    }
+
+    #[test]
+    fn test_every_digit_is_numeric() {
+        let tokens = DigitPolicy::Unicode.non_ascii_tokens();
+        assert_eq!(tokens.len(), 650);
+        for (token, value) in &tokens {
+            let c = token.chars().next().unwrap();
+            assert!(c.is_numeric(), "{:?}", c);
+            assert_eq!(DigitPolicy::Unicode.digit_value(c), Some(*value));
+        }
+        assert_eq!(tokens[3], ("٣".to_string(), 3));
+        assert_eq!(DigitPolicy::Ascii.non_ascii_tokens(), vec![]);
+    }
+
+    #[test]
+    fn test_non_digits() {
+        assert_eq!(DigitPolicy::Ascii.non_digits("a1½b٣"), vec![(2, '½'), (5, '٣')]);
+        assert_eq!(DigitPolicy::Unicode.non_digits("a1½b٣Ⅻ"), vec![(2, '½'), (7, 'Ⅻ')]);
+        assert_eq!(DigitPolicy::Ascii.non_digits("a1b2"), vec![]);
+        assert_eq!(describe_non_digit(2, '½'), "½ at 2 is numeric but not a decimal digit");
+        assert_eq!(describe_non_digit(5, '٣'), "٣ at 5 is a decimal digit but not ASCII (see --digits unicode)");
+    }
+}
@@ -0,0 +1,125 @@
+use std::fs;
+use std::io;
+
+/*
+ * The words that stand for digits in a calibration document, as (token, value): built in for a few
+ * languages, or read from a vocabulary file with a token and its digit on each line, e.g.
+ *
+ * # Dutch
+ * nul 0
+ * een 1
+ * ...
+ *
+ * Blank lines and lines starting with # are ignored. Tokens are matched exactly, so are given in
+ * lower case, as they appear in documents.
+ */
+
+pub const ENGLISH: [(&str, u32); 10] = [
+    ("zero", 0), ("one", 1), ("two", 2), ("three", 3), ("four", 4),
+    ("five", 5), ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9),
+];
+
+pub const FRENCH: [(&str, u32); 10] = [
+    ("zéro", 0), ("un", 1), ("deux", 2), ("trois", 3), ("quatre", 4),
+    ("cinq", 5), ("six", 6), ("sept", 7), ("huit", 8), ("neuf", 9),
+];
+
+pub const GERMAN: [(&str, u32); 10] = [
+    ("null", 0), ("eins", 1), ("zwei", 2), ("drei", 3), ("vier", 4),
+    ("fünf", 5), ("sechs", 6), ("sieben", 7), ("acht", 8), ("neun", 9),
+];
+
+pub const SPANISH: [(&str, u32); 10] = [
+    ("cero", 0), ("uno", 1), ("dos", 2), ("tres", 3), ("cuatro", 4),
+    ("cinco", 5), ("seis", 6), ("siete", 7), ("ocho", 8), ("nueve", 9),
+];
+
+pub const BUILT_IN: [(&str, &[(&str, u32)]); 4] = [
+    ("english", &ENGLISH),
+    ("french", &FRENCH),
+    ("german", &GERMAN),
+    ("spanish", &SPANISH),
+];
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/*
+ * Parses the contents of a vocabulary file, named source in errors.
+ */
+pub fn parse(text: &str, source: &str) -> io::Result<Vec<(String, u32)>> {
+    let mut tokens = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (token, value) = match words.as_slice() {
+            [token, value] => (token, value),
+            _ => return Err(invalid(format!("{}: line {}: expected a token and its digit", source, i + 1))),
+        };
+        match value.parse::<u32>() {
+            Ok(value) if value <= 9 => tokens.push((token.to_string(), value)),
+            _ => return Err(invalid(format!("{}: line {}: {} is not a digit", source, i + 1, value))),
+        }
+    }
+    Ok(tokens)
+}
+
+/*
+ * A built-in vocabulary by name, or otherwise the vocabulary file at that path.
+ */
+pub fn load(name: &str) -> io::Result<Vec<(String, u32)>> {
+    match BUILT_IN.iter().find(|&&(built_in, _)| built_in == name) {
+        Some(&(_, tokens)) => Ok(tokens.iter().map(|&(token, value)| (token.to_string(), value)).collect()),
+        None => {
+            let text = fs::read_to_string(name).map_err(|error| {
+                io::Error::new(error.kind(), format!("{}: not a built-in vocabulary, nor a file that can be read ({})", name, error))
+            })?;
+            parse(&text, name)
+        }
+    }
+}
+
+/*
+ * The vocabularies combined into one. A token may be in several as long as it stands for the same
+ * digit in each (as "six" does in English and French).
+ */
+pub fn combine(vocabularies: Vec<Vec<(String, u32)>>) -> io::Result<Vec<(String, u32)>> {
+    let mut combined: Vec<(String, u32)> = Vec::new();
+    for (token, value) in vocabularies.into_iter().flatten() {
+        match combined.iter().find(|(known, _)| *known == token) {
+            Some(&(_, known_value)) if known_value != value => {
+                return Err(invalid(format!("{} is {} in one vocabulary and {} in another", token, known_value, value)));
+            }
+            Some(_) => {}
+            None => combined.push((token, value)),
+        }
+    }
+    Ok(combined)
+}
+
+#[cfg(test)]
+mod vocabulary_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let tokens = parse("# Dutch\nnul 0\n\n  een 1  \n", "dutch.txt").unwrap();
+        assert_eq!(tokens, vec![("nul".to_string(), 0), ("een".to_string(), 1)]);
+        assert_eq!(parse("twee\n", "dutch.txt").unwrap_err().to_string(), "dutch.txt: line 1: expected a token and its digit");
+        assert_eq!(parse("tien 10\n", "dutch.txt").unwrap_err().to_string(), "dutch.txt: line 1: 10 is not a digit");
+    }
+
+    #[test]
+    fn test_combine() {
+        let combined = combine(vec![load("english").unwrap(), load("french").unwrap()]).unwrap();
+        assert_eq!(combined.len(), 19, "six is in both");
+        assert!(combined.contains(&("quatre".to_string(), 4)));
+
+        let error = combine(vec![load("english").unwrap(), vec![("one".to_string(), 2)]]).unwrap_err();
+        assert_eq!(error.to_string(), "one is 1 in one vocabulary and 2 in another");
+    }
+}
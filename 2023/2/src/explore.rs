@@ -0,0 +1,76 @@
+use std::str;
+use common::answer::{self, Answer};
+use common::repl::{argument, Explorer};
+use common::solver::SolveError;
+
+use crate::{game_id, max_cubes, ALLOWED_MAX_CUBES, COLOUR_NAMES};
+
+pub struct GamesExplorer {
+    games: Vec<String>,
+}
+
+/*
+ * Reads the record of games once, to look them up by id.
+ */
+pub fn explorer(input: &str) -> Result<Box<dyn Explorer>, SolveError> {
+    Ok(Box::new(GamesExplorer { games: input.lines().map(str::to_string).collect() }))
+}
+
+impl GamesExplorer {
+    fn game(&self, id: Answer) -> Result<String, String> {
+        let game = self.games.iter()
+            .find(|game| game_id(game.as_bytes()) == Some(id))
+            .ok_or_else(|| format!("no game {}", id))?;
+        let cubes = max_cubes(game.as_bytes());
+
+        let colour = |index: usize| str::from_utf8(COLOUR_NAMES[index]).expect("colour names are ASCII");
+        let most: Vec<String> = cubes.iter()
+            .enumerate()
+            .filter_map(|(index, count)| count.map(|count| format!("{} {}", count, colour(index))))
+            .collect();
+        let too_many: Vec<String> = cubes.iter()
+            .zip(ALLOWED_MAX_CUBES)
+            .enumerate()
+            .filter(|(_, (count, allowed))| count.is_some_and(|count| count > *allowed))
+            .map(|(index, (_, allowed))| format!("more than {} {}", allowed, colour(index)))
+            .collect();
+        let possible = match too_many.as_slice() {
+            [] => "possible".to_string(),
+            too_many => format!("impossible ({})", too_many.join(", ")),
+        };
+        let power = answer::product(cubes.into_iter().flatten().map(Answer::from), "multiplying cube counts")
+            .map_err(|error| error.to_string())?;
+        Ok(format!("{}\nat most {}: {}, power {}", game, most.join(", "), possible, power))
+    }
+}
+
+impl Explorer for GamesExplorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[("game ID", "show the most cubes of each colour drawn in a game, whether it is possible and its power")]
+    }
+
+    fn command(&mut self, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("game", [_]) => self.game(argument(args, 0, "game id")?),
+            _ => Err(format!("unknown command {}, try help", [&[name], args].concat().join(" "))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod explore_tests {
+    use super::*;
+
+    #[test]
+    fn test_game() {
+        let mut explorer = explorer(include_str!("../example_record.txt")).unwrap();
+        assert_eq!(explorer.command("game", &["1"]), Ok("\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+at most 4 red, 2 green, 6 blue: possible, power 48".to_string()));
+        assert_eq!(
+            explorer.command("game", &["3"]).unwrap().lines().nth(1),
+            Some("at most 20 red, 13 green, 6 blue: impossible (more than 12 red), power 1560"),
+        );
+        assert_eq!(explorer.command("game", &["6"]), Err("no game 6".to_string()));
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt::Write;
+use std::io::{self, BufRead};
+use std::str;
+use common::answer::{self, Answer};
+use common::budget::checkpoint;
+use common::grammar::{Grammar, Rule, Token};
+use common::random::Rng;
+use common::solver::{Solution, SolveError};
+
+const COLOURS: Token = Token::OneOf(&["red", "green", "blue"]);
+const COLOUR_NAMES: [&[u8]; 3] = [b"red", b"green", b"blue"];
+const ALLOWED_MAX_CUBES: [u32; 3] = [12, 13, 14];
+// Lines read between checks of the run's budget
+const LINES_PER_CHECK: usize = 1 << 12;
+
+pub mod explore;
+
+/*
+ * A game per line, e.g. "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green": draws of cubes
+ * separated by ", " within a handful, and by "; " between handfuls.
+ */
+pub const GRAMMAR: Grammar = Grammar(&[Rule::Lines(&[
+    Token::Literal("Game "),
+    Token::Number,
+    Token::Literal(": "),
+    Token::Number,
+    Token::Literal(" "),
+    COLOURS,
+    Token::Many(&[Token::OneOf(&[", ", "; "]), Token::Number, Token::Literal(" "), COLOURS]),
+])]);
+
+/*
+ * The most cubes of each colour (red, green, blue) drawn in a game, or None for a colour never
+ * drawn. A draw is a number followed by a space and a colour, as in "3 blue".
+ */
+fn max_cubes(line: &[u8]) -> [Option<u32>; 3] {
+    let mut max_cubes = [None; 3];
+    let mut rest = line;
+    while let Some(start) = rest.iter().position(u8::is_ascii_digit) {
+        let digits = rest[start..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+        let (number, after) = rest[start..].split_at(digits);
+        rest = after;
+        let Some((_, after_space)) = after.split_first().filter(|(byte, _)| byte.is_ascii_whitespace()) else {
+            continue;
+        };
+        let colour = COLOUR_NAMES.iter().position(|name| after_space.starts_with(name));
+        let value = str::from_utf8(number).ok().and_then(|value| value.parse::<u32>().ok());
+        if let (Some(colour), Some(value)) = (colour, value) {
+            max_cubes[colour] = Some(max_cubes[colour].map_or(value, |max: u32| max.max(value)));
+        }
+    }
+    max_cubes
+}
+
+/*
+ * The id of a game, from the start of its line.
+ */
+fn game_id(line: &[u8]) -> Option<Answer> {
+    let rest = line.strip_prefix(b"Game ")?;
+    let digits = rest.iter().take_while(|byte| byte.is_ascii_digit()).count();
+    str::from_utf8(&rest[..digits]).ok()?.parse::<Answer>().ok()
+}
+
+/*
+ * Sums the ids of the games possible with at most 12 red, 13 green and 14 blue cubes.
+ */
+pub fn sum_games(reader: impl BufRead) -> Result<Solution, SolveError> {
+    let lines = reader.lines().collect::<io::Result<Vec<String>>>()?;
+    sum_games_lines(lines)
+}
+
+/*
+ * As sum_games, for lines already in memory (e.g. borrowed from a MappedInput).
+ */
+pub fn sum_games_lines(lines: impl IntoIterator<Item = impl AsRef<[u8]>>) -> Result<Solution, SolveError> {
+    let mut total = Answer::from(0u32);
+    for (i, line) in lines.into_iter().enumerate() {
+        if i % LINES_PER_CHECK == 0 {
+            checkpoint()?;
+        }
+        let line = line.as_ref();
+        let possible = max_cubes(line)
+            .iter()
+            .zip(ALLOWED_MAX_CUBES)
+            .all(|(value, allowed)| value.is_none_or(|value| value <= allowed));
+        if let Some(id) = game_id(line).filter(|_| possible) {
+            total = answer::add(total, id, "summing game ids")?;
+        }
+    }
+
+    Ok(Solution::new(total, Vec::new()))
+}
+
+/*
+ * Sums the powers (products of the fewest cubes of each colour) of all games.
+ */
+pub fn sum_powers(reader: impl BufRead) -> Result<Solution, SolveError> {
+    let lines = reader.lines().collect::<io::Result<Vec<String>>>()?;
+    sum_powers_lines(lines)
+}
+
+/*
+ * As sum_powers, for lines already in memory (e.g. borrowed from a MappedInput).
+ */
+pub fn sum_powers_lines(lines: impl IntoIterator<Item = impl AsRef<[u8]>>) -> Result<Solution, SolveError> {
+    let mut total = Answer::from(0u32);
+    for (i, line) in lines.into_iter().enumerate() {
+        if i % LINES_PER_CHECK == 0 {
+            checkpoint()?;
+        }
+        let power = answer::product(max_cubes(line.as_ref()).into_iter().flatten().map(Answer::from), "multiplying cube counts")?;
+        total = answer::add(total, power, "summing powers")?;
+    }
+
+    Ok(Solution::new(total, Vec::new()))
+}
+
+/*
+ * Generates a record of the number of games given, numbered from first: up to six handfuls each,
+ * of up to 20 cubes of one to three colours, so that about a fifth of the games are possible.
+ */
+pub fn generate_record(rng: &mut Rng, first: usize, games: usize) -> String {
+    let mut record = String::new();
+    for id in first..first + games {
+        write!(record, "Game {}:", id).unwrap();
+        for handful in 0..rng.range(1..7) {
+            let separator = if handful == 0 { " " } else { "; " };
+            let colours: Vec<&str> = ["red", "green", "blue"].into_iter().filter(|_| !rng.one_in(3)).collect();
+            let colours = if colours.is_empty() { vec!["blue"] } else { colours };
+            for (i, colour) in colours.iter().enumerate() {
+                write!(record, "{}{} {}", if i == 0 { separator } else { ", " }, rng.range(1..21), colour).unwrap();
+            }
+        }
+        record.push('\n');
+    }
+    record
+}
+
+#[cfg(test)]
+mod games_tests {
+    use super::*;
+
+    #[test]
+    fn test_unreadable_line_fails() {
+        let record = include_bytes!("../example_record.txt");
+        assert_eq!(sum_games(&record[..]).unwrap().answer, "8");
+        assert_eq!(sum_powers(&record[..]).unwrap().answer, "2286");
+
+        let unreadable = [&record[..], b"Game 6: 1 red\xff\n"].concat();
+        assert!(sum_games(&unreadable[..]).is_err());
+        assert!(sum_powers(&unreadable[..]).is_err());
+    }
+
+    #[test]
+    fn test_generate_record() {
+        let record = generate_record(&mut Rng::new(2), 1, 200);
+        assert!(GRAMMAR.validate(&record).is_empty());
+        assert_eq!(record.lines().count(), 200);
+        assert_eq!(sum_games(record.as_bytes()).unwrap().answer, sum_games_lines(record.lines()).unwrap().answer);
+    }
+}
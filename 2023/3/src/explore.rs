@@ -0,0 +1,126 @@
+use std::ops::Range;
+use itertools::Itertools;
+use common::answer::{self, Answer};
+use common::repl::{argument, Explorer};
+use common::solver::SolveError;
+
+use crate::parts::number_spans;
+
+pub struct SchematicExplorer {
+    rows: Vec<String>,
+}
+
+/*
+ * Reads the schematic once, to look at what is around a position in it.
+ */
+pub fn explorer(input: &str) -> Result<Box<dyn Explorer>, SolveError> {
+    Ok(Box::new(SchematicExplorer { rows: input.lines().map(str::to_string).collect() }))
+}
+
+fn is_symbol(byte: u8) -> bool {
+    byte != b'.' && byte.is_ascii_punctuation()
+}
+
+impl SchematicExplorer {
+    fn byte(&self, row: usize, column: usize) -> Option<u8> {
+        self.rows.get(row)?.as_bytes().get(column).copied()
+    }
+
+    /*
+     * The numbers with a digit in the rows and columns given, as (row, span of columns).
+     */
+    fn numbers_within(&self, rows: Range<usize>, columns: Range<usize>) -> Vec<(usize, Range<usize>)> {
+        rows.filter(|&row| row < self.rows.len())
+            .flat_map(|row| {
+                number_spans(&self.rows[row])
+                    .into_iter()
+                    .filter(|span| span.start < columns.end && columns.start < span.end)
+                    .map(move |span| (row, span))
+            })
+            .collect()
+    }
+
+    fn number(&self, row: usize, span: &Range<usize>) -> &str {
+        &self.rows[row][span.clone()]
+    }
+
+    fn at(&self, row: usize, column: usize) -> Result<String, String> {
+        let byte = row.checked_sub(1)
+            .zip(column.checked_sub(1))
+            .and_then(|(row, column)| self.byte(row, column))
+            .ok_or_else(|| format!("row {}, column {} is outside the schematic", row, column))?;
+        let (row, column) = (row - 1, column - 1);
+        let position = format!("'{}' at row {}, column {}", byte as char, row + 1, column + 1);
+
+        if byte.is_ascii_digit() {
+            let (_, span) = self.numbers_within(row..row + 1, column..column + 1).remove(0);
+            let symbols: Vec<String> = (row.saturating_sub(1)..row + 2)
+                .flat_map(|r| (span.start.saturating_sub(1)..span.end + 1).map(move |c| (r, c)))
+                .filter_map(|(r, c)| self.byte(r, c).filter(|&byte| is_symbol(byte)).map(|byte| (r, c, byte)))
+                .map(|(r, c, byte)| format!("'{}' at {},{}", byte as char, r + 1, c + 1))
+                .collect();
+            let number = format!("{}, in {} (columns {} to {})", position, self.number(row, &span), span.start + 1, span.end);
+            Ok(match symbols.as_slice() {
+                [] => format!("{}: not a part number, as no symbol is next to it", number),
+                symbols => format!("{}: a part number, next to {}", number, symbols.join(", ")),
+            })
+        } else if is_symbol(byte) {
+            let numbers: Vec<&str> = self.numbers_within(row.saturating_sub(1)..row + 2, column.saturating_sub(1)..column + 2)
+                .iter()
+                .map(|(row, span)| self.number(*row, span))
+                .collect();
+            let ratios = || -> Result<Answer, String> {
+                let products = numbers.iter()
+                    .map(|number| answer::parse_digits(number, "reading a part number"))
+                    .collect::<Result<Vec<Answer>, _>>()
+                    .map_err(|error| error.to_string())?
+                    .into_iter()
+                    .tuple_combinations()
+                    .map(|(a, b)| answer::mul(a, b, "multiplying gear ratios"))
+                    .collect::<Result<Vec<Answer>, _>>()
+                    .map_err(|error| error.to_string())?;
+                answer::sum(products, "summing gear ratios").map_err(|error| error.to_string())
+            };
+            Ok(match (numbers.as_slice(), byte) {
+                ([], _) => format!("{}: next to no numbers", position),
+                (numbers, b'*') if numbers.len() > 1 => {
+                    format!("{}: next to {}, adding {} to the gear ratios", position, numbers.join(", "), ratios()?)
+                }
+                (numbers, _) => format!("{}: next to {}", position, numbers.join(", ")),
+            })
+        } else {
+            Ok(format!("{}: empty", position))
+        }
+    }
+}
+
+impl Explorer for SchematicExplorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[("at ROW COLUMN", "show what is at a position (from 1) and the numbers or symbols next to it")]
+    }
+
+    fn command(&mut self, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("at", [_, _]) => self.at(argument(args, 0, "row")?, argument(args, 1, "column")?),
+            _ => Err(format!("unknown command {}, try help", [&[name], args].concat().join(" "))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod explore_tests {
+    use super::*;
+
+    #[test]
+    fn test_at() {
+        let explorer = SchematicExplorer { rows: include_str!("../example_schematic.txt").lines().map(str::to_string).collect() };
+        assert_eq!(explorer.at(1, 3), Ok("'7' at row 1, column 3, in 467 (columns 1 to 3): a part number, next to '*' at 2,4".to_string()));
+        assert_eq!(explorer.at(1, 7), Ok("'1' at row 1, column 7, in 114 (columns 6 to 8): not a part number, as no symbol is next to it".to_string()));
+        assert_eq!(explorer.at(2, 4), Ok("'*' at row 2, column 4: next to 467, 35, adding 16345 to the gear ratios".to_string()));
+        assert_eq!(explorer.at(5, 4), Ok("'*' at row 5, column 4: next to 617".to_string()));
+        assert_eq!(explorer.at(4, 7), Ok("'#' at row 4, column 7: next to 633".to_string()));
+        assert_eq!(explorer.at(1, 4), Ok("'.' at row 1, column 4: empty".to_string()));
+        assert_eq!(explorer.at(0, 1), Err("row 0, column 1 is outside the schematic".to_string()));
+        assert_eq!(explorer.at(11, 1), Err("row 11, column 1 is outside the schematic".to_string()));
+    }
+}
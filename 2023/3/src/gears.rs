@@ -0,0 +1,121 @@
+use std::io::{self, BufRead};
+use std::collections::HashSet;
+use itertools::Itertools;
+use common::answer::{self, Answer, Overflow};
+use common::budget::checkpoint;
+use common::solver::{Solution, SolveError};
+
+fn find_symbol_positions(line: &str) -> HashSet<usize> {
+    line.chars()
+        .enumerate()
+        .filter(|&(_, c)| c == '*')
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/*
+ * Return int value of contiguous digits passing through string index given.
+ *
+ * Given a position in a string, identify if there is a digit char at that position and if there
+ * is, uncover the remaining contiguous digits forward and backward. Evaluate those contiguous
+ * digits and return the integer value. Otherwise return None. Fails if the value is too large.
+ */
+fn parse_contiguous_digits(s: &str, position: usize) -> Result<Option<Answer>, Overflow> {
+    let bytes = s.as_bytes();
+    if !bytes.get(position).is_some_and(u8::is_ascii_digit) {
+        return Ok(None);
+    }
+    let mut start = position;
+    let mut end = position;
+    while start > 0 && bytes[start - 1].is_ascii_digit() {
+        start -= 1;
+    }
+    while end < s.len() - 1 && bytes[end + 1].is_ascii_digit() {
+        end += 1;
+    }
+    answer::parse_digits(&s[start..=end], "reading a part number").map(Some)
+}
+
+/*
+ * Sums the gear ratios: products of pairs of numbers adjacent to the same '*' symbol.
+ */
+pub fn sum_gears(reader: impl BufRead) -> Result<Solution, SolveError> {
+    let lines: Vec<String> = reader.lines().collect::<io::Result<_>>()?;
+    let mut diagnostics = Vec::new();
+
+    let preprocessed_lines_iter = std::iter::once(String::new()) // Dummy line before
+        .chain(lines)
+        .chain(std::iter::once(String::new())) // Dummy line after
+        .map(|line| (find_symbol_positions(&line), line));
+
+    let line_sums = preprocessed_lines_iter
+        .tuple_windows::<(_, _, _)>()
+        .map(|window| -> Result<Answer, SolveError> {
+            checkpoint()?;
+            let (_,                line_before ) = &window.0;
+            let (symbol_positions, line        ) = &window.1;
+            let (_,                line_after  ) = &window.2;
+
+            let gear_sums = symbol_positions
+                .iter()
+                .map(|&position| {
+                    // Parse numbers seen around symbol position:
+                    // nw n ne
+                    //  w * e
+                    // sw s se
+                    // And then sum the product of all meaningful pairings.
+                    //
+                    // Certain pairings do not make sense to consider. E.g. nw*n, because those
+                    // are contiguous. So if we parse a digit at n, then nw and ne are not
+                    // considered (set to None).
+                    // A symbol at the start of a line has nothing to its west
+                    let west = |line: &str| position.checked_sub(1).map_or(Ok(None), |west| parse_contiguous_digits(line, west));
+                    let w = west(line)?;
+                    let e = parse_contiguous_digits(line, position + 1)?;
+
+                    let n = parse_contiguous_digits(line_before, position)?;
+                    let (nw, ne) = match n {
+                        Some(_) => (None, None),
+                        None => (west(line_before)?, parse_contiguous_digits(line_before, position + 1)?),
+                    };
+
+                    let s = parse_contiguous_digits(line_after, position)?;
+                    let (sw, se) = match s {
+                        Some(_) => (None, None),
+                        None => (west(line_after)?, parse_contiguous_digits(line_after, position + 1)?),
+                    };
+                    if false {
+                        println!("{:?} {:?} {:?}; {:?} * {:?}; {:?} {:?} {:?}", nw, n, ne, w, e, sw, s, se);
+                    }
+                    let products = [nw, n, ne, w, e, sw, s, se]
+                        .into_iter()
+                        .flatten()
+                        .tuple_combinations()
+                        .map(|(a, b)| answer::mul(a, b, "multiplying gear ratios"))
+                        .collect::<Result<Vec<Answer>, Overflow>>()?;
+                    answer::sum(products, "summing gear ratios")
+                })
+                .collect::<Result<Vec<Answer>, Overflow>>()?;
+            let sum = answer::sum(gear_sums, "summing gear ratios")?;
+            diagnostics.push(format!("{} => {}", line, sum));
+            Ok(sum)
+        })
+        .collect::<Result<Vec<Answer>, SolveError>>()?;
+    let total = answer::sum(line_sums, "summing gear ratios")?;
+
+    Ok(Solution::new(total, diagnostics))
+}
+
+#[cfg(test)]
+mod gears_tests {
+    use super::*;
+
+    #[test]
+    fn test_symbols_at_line_ends() {
+        assert_eq!(sum_gears(&b"*1\n"[..]).unwrap().answer, "0");
+        assert_eq!(sum_gears(&b"2*3\n...\n*..\n4..\n"[..]).unwrap().answer, "6");
+        assert_eq!(sum_gears(&b"..2\n..*\n..5\n"[..]).unwrap().answer, "10");
+        assert_eq!(sum_gears(&b"*\n"[..]).unwrap().answer, "0");
+        assert!(sum_gears(&b"2*3\n\xff\n"[..]).is_err());
+    }
+}
@@ -0,0 +1,12 @@
+use common::grammar::{Grammar, Rule};
+
+pub mod explore;
+pub mod gears;
+pub mod parts;
+
+/*
+ * An engine schematic: a grid of '.', digits and symbols.
+ */
+pub const GRAMMAR: Grammar = Grammar(&[
+    Rule::Grid(|c| c.is_ascii_digit() || c.is_ascii_punctuation(), "'.', digit or symbol"),
+]);
@@ -0,0 +1,161 @@
+use std::io::{self, BufRead};
+use std::collections::HashSet;
+use std::ops::Range;
+use itertools::Itertools;
+use common::answer::{self, Answer, Overflow};
+use common::budget::checkpoint;
+use common::frames::{Colour, Frame, FrameSink};
+use common::solver::{Solution, SolveError};
+
+fn find_symbol_positions(line: &str) -> HashSet<usize> {
+    line.chars()
+        .enumerate()
+        .filter(|&(_, c)| c != '.' && c.is_ascii_punctuation())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn visualize_symbol_positions(symbol_positions: &HashSet<usize>, line_length: usize) -> String {
+    (0..line_length)
+        .map(|i| if symbol_positions.contains(&i) { '^' } else { ' ' })
+        .collect()
+}
+
+/*
+ * Sums numbers found in a string touched by positions given
+ *
+ * This function takes a string input line e.g.:
+ * ...10.....20.....30......40
+ * And a set of positions
+ * 2, 12, 17, 22
+ * and returns the sum of all numbers formed from contiguous digits touched by these positions.
+ *
+ * For example:
+ * Position 2 touches the 10:
+ * ..X10
+ * Whilst position 17 touches the 30:
+ * ...10.....20.....X0
+ * 20 is also touched, but 40 is not. So the return result is 60.
+ *
+ */
+fn sum_touched_numbers(line: &str, touch_positions: &HashSet<usize>) -> Result<Answer, Overflow> {
+    let touched_numbers = number_spans(line)
+        .into_iter()
+        .filter(|span| is_touched(span, line.len(), touch_positions))
+        .map(|span| answer::parse_digits(&line[span], "reading a part number"))
+        .collect::<Result<Vec<Answer>, Overflow>>()?;
+    answer::sum(touched_numbers, "summing part numbers")
+}
+
+/*
+ * Returns the index ranges of all runs of contiguous digits in a string.
+ */
+pub(crate) fn number_spans(line: &str) -> Vec<Range<usize>> {
+    let bytes = line.as_bytes();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i].is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            spans.push(start..i);
+        } else {
+            i += 1;
+        }
+    }
+    spans
+}
+
+/*
+ * Whether any position within a span, or immediately either side of it, is one of the positions
+ * given.
+ */
+fn is_touched(span: &Range<usize>, line_length: usize, touch_positions: &HashSet<usize>) -> bool {
+    (span.start.saturating_sub(1)..(span.end + 1).min(line_length)).any(|i| touch_positions.contains(&i))
+}
+
+/*
+ * Frame of the whole schematic, with symbols highlighted, for the scan to colour in.
+ */
+fn scan_frame(lines: &[String]) -> Frame {
+    let mut frame = Frame::from_lines(lines);
+    for (y, line) in lines.iter().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            if c == '.' {
+                frame.paint(x, y, Colour::Dim);
+            }
+        }
+        for x in find_symbol_positions(line) {
+            frame.paint(x, y, Colour::Yellow);
+        }
+    }
+    frame
+}
+
+fn combine_symbol_positions<'a>(positions: impl IntoIterator<Item = &'a HashSet<usize>>) -> HashSet<usize> {
+    positions.into_iter().flat_map(|set| set.iter().cloned()).collect()
+}
+
+/*
+ * Sums the part numbers: numbers adjacent, even diagonally, to a symbol.
+ *
+ * Emits a frame per line of the schematic into frames, colouring part numbers green and other
+ * numbers red as the scan reaches them.
+ */
+pub fn sum_parts(reader: impl BufRead, frames: &mut dyn FrameSink) -> Result<Solution, SolveError> {
+    let lines: Vec<String> = reader.lines().collect::<io::Result<_>>()?;
+    let mut scan = if frames.enabled() { scan_frame(&lines) } else { Frame::new(0, 0) };
+    let mut diagnostics = Vec::new();
+
+    let preprocessed_lines_iter = std::iter::once(String::new()) // Dummy line before
+        .chain(lines.iter().cloned())
+        .chain(std::iter::once(String::new())) // Dummy line after
+        .map(|line| (find_symbol_positions(&line), line));
+
+    let line_sums = preprocessed_lines_iter
+        .tuple_windows::<(_, _, _)>()
+        .enumerate()
+        .map(|(y, window)| -> Result<Answer, SolveError> {
+            checkpoint()?;
+            let (symbol_positions0, _   ) = &window.0;
+            let (symbol_positions1, line) = &window.1;
+            let (symbol_positions2, _   ) = &window.2;
+            let combined_positions = &combine_symbol_positions(vec![symbol_positions0, symbol_positions1, symbol_positions2]);
+            let sum = sum_touched_numbers(line, combined_positions)?;
+            diagnostics.push(format!("{} => {}", line, sum));
+            if false {
+                println!("{}", visualize_symbol_positions(combined_positions, line.len()));
+            }
+            if frames.enabled() {
+                // Colour numbers as the scan reaches them: green if counted as parts, red if not
+                for span in number_spans(line) {
+                    let colour = if is_touched(&span, line.len(), combined_positions) { Colour::Green } else { Colour::Red };
+                    for x in span {
+                        scan.paint(x, y, colour);
+                    }
+                }
+                frames.emit(scan.clone().with_caption(format!("{} => {}", line, sum)));
+            }
+            Ok(sum)
+        })
+        .collect::<Result<Vec<Answer>, SolveError>>()?;
+    let total = answer::sum(line_sums, "summing part numbers")?;
+
+    Ok(Solution::new(total, diagnostics))
+}
+
+#[cfg(test)]
+mod sum_touched_numbers_tests {
+    use super::*;
+
+    #[test]
+    fn test_sum_touched_numbers() {
+        let line = "...10.....20.....30......40";
+        let positions = HashSet::from([2, 12, 17, 22]);
+        assert_eq!(sum_touched_numbers(line, &positions), Ok(Answer::from(60u32)));
+        assert_eq!(number_spans(line), vec![3..5, 10..12, 17..19, 25..27]);
+        assert!(!is_touched(&(25..27), line.len(), &positions));
+    }
+}
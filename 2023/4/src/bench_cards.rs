@@ -0,0 +1,60 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::env;
+use std::time::Instant;
+use common::input::MappedInput;
+use common::random::Rng;
+use common::solver::{Solution, SolveError};
+
+const USAGE: &str = "usage: bench_cards FILE
+       bench_cards --generate MEGABYTES [--seed N] > FILE
+times both parts on cards, reading their lines into Strings and borrowing them from the mapped
+file, or generates cards to time them on";
+
+fn generate(megabytes: u64, seed: u64) -> io::Result<()> {
+    let mut rng = Rng::new(seed);
+    let mut output = BufWriter::new(io::stdout().lock());
+    let (mut written, mut cards) = (0, 0);
+    while written < megabytes * 1_000_000 {
+        let chunk = day4::generate_cards(&mut rng, cards + 1, 10_000);
+        output.write_all(chunk.as_bytes())?;
+        written += chunk.len() as u64;
+        cards += 10_000;
+    }
+    output.flush()
+}
+
+fn time(name: &str, megabytes: f64, solve: impl FnOnce() -> Result<Solution, SolveError>) -> io::Result<()> {
+    let started = Instant::now();
+    let solution = solve()?;
+    let elapsed = started.elapsed().as_secs_f64();
+    println!("{:<14} {:>8.3}s {:>8.0}MB/s  Total: {}", name, elapsed, megabytes / elapsed, solution.answer);
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().collect();
+    let usage_error = || io::Error::new(io::ErrorKind::InvalidInput, USAGE);
+    match &args[1..] {
+        [generate_flag, megabytes, rest @ ..] if generate_flag == "--generate" => {
+            let megabytes = megabytes.parse().map_err(|_| usage_error())?;
+            let seed = match rest {
+                [] => 2023,
+                [seed_flag, seed] if seed_flag == "--seed" => seed.parse().map_err(|_| usage_error())?,
+                _ => return Err(usage_error()),
+            };
+            generate(megabytes, seed)
+        }
+        [file_path] if !file_path.starts_with("--") => {
+            let input = MappedInput::open(file_path)?;
+            let megabytes = input.bytes().len() as f64 / 1e6;
+            println!("{:.0}MB, {} lines", megabytes, input.lines().count());
+
+            time("part 1 strings", megabytes, || day4::sum_points(BufReader::new(File::open(file_path)?)))?;
+            time("part 1 mapped", megabytes, || day4::sum_points_lines(input.lines()))?;
+            time("part 2 strings", megabytes, || day4::sum_cards(BufReader::new(File::open(file_path)?)))?;
+            time("part 2 mapped", megabytes, || day4::sum_cards_lines(input.lines()))
+        }
+        _ => Err(usage_error()),
+    }
+}
@@ -0,0 +1,86 @@
+use common::answer::Answer;
+use common::repl::{argument, Explorer};
+use common::solver::SolveError;
+
+use crate::{card_copies, matching_numbers, points};
+
+pub struct CardsExplorer {
+    cards: Vec<String>,
+    match_counts: Vec<usize>,
+    // Worked out the first time they are asked for
+    copies: Option<Vec<Answer>>,
+}
+
+/*
+ * Reads the cards once, to look them up by number.
+ */
+pub fn explorer(input: &str) -> Result<Box<dyn Explorer>, SolveError> {
+    let cards: Vec<String> = input.lines().map(str::to_string).collect();
+    let match_counts = cards.iter().map(|card| matching_numbers(card.as_bytes()).len()).collect();
+    Ok(Box::new(CardsExplorer { cards, match_counts, copies: None }))
+}
+
+fn plural(count: impl std::fmt::Display, one: &str, many: &str) -> String {
+    let count = count.to_string();
+    format!("{} {}", count, if count == "1" { one } else { many })
+}
+
+impl CardsExplorer {
+    fn card(&mut self, number: usize) -> Result<String, String> {
+        let index = number.checked_sub(1).filter(|&index| index < self.cards.len())
+            .ok_or_else(|| format!("no card {} (there are {})", number, self.cards.len()))?;
+        let matching = matching_numbers(self.cards[index].as_bytes());
+        let points = points(matching.len()).map_err(|error| error.to_string())?;
+        if self.copies.is_none() {
+            self.copies = Some(card_copies(&self.match_counts).map_err(|error| error.to_string())?);
+        }
+        let copies = &self.copies.as_ref().expect("copies were just counted")[index];
+
+        let matches = match matching.as_slice() {
+            [] => "no matches".to_string(),
+            numbers => {
+                let numbers: Vec<String> = numbers.iter().map(i32::to_string).collect();
+                format!("{} ({})", plural(numbers.len(), "match", "matches"), numbers.join(", "))
+            }
+        };
+        let wins = match matching.len() {
+            0 => String::new(),
+            1 => format!(", wins a copy of card {}", number + 1),
+            count => format!(", wins a copy each of cards {} to {}", number + 1, number + count),
+        };
+        Ok(format!(
+            "{}\n{}, worth {}{}; {} held",
+            self.cards[index], matches, plural(points, "point", "points"), wins, plural(copies, "copy", "copies"),
+        ))
+    }
+}
+
+impl Explorer for CardsExplorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[("card N", "show card N's matching numbers, points, the cards it wins and its copies")]
+    }
+
+    fn command(&mut self, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("card", [_]) => self.card(argument(args, 0, "card number")?),
+            _ => Err(format!("unknown command {}, try help", [&[name], args].concat().join(" "))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod explore_tests {
+    use super::*;
+
+    #[test]
+    fn test_card() {
+        let mut explorer = explorer(include_str!("../example_cards.txt")).unwrap();
+        assert_eq!(explorer.command("card", &["3"]), Ok("\
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+2 matches (1, 21), worth 2 points, wins a copy each of cards 4 to 5; 4 copies held".to_string()));
+        assert_eq!(explorer.command("card", &["4"]).unwrap().lines().nth(1), Some("1 match (84), worth 1 point, wins a copy of card 5; 8 copies held"));
+        assert_eq!(explorer.command("card", &["6"]).unwrap().lines().nth(1), Some("no matches, worth 0 points; 1 copy held"));
+        assert_eq!(explorer.command("card", &["7"]), Err("no card 7 (there are 6)".to_string()));
+        assert_eq!(explorer.command("card", &["0"]), Err("no card 0 (there are 6)".to_string()));
+    }
+}
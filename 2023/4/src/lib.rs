@@ -0,0 +1,195 @@
+use std::fmt::Write;
+use std::io::{self, BufRead};
+use std::mem;
+use std::str;
+use common::answer::{self, Answer, Overflow};
+use common::budget::checkpoint;
+use common::dp::DpTable;
+use common::grammar::{Grammar, Rule, Token};
+use common::random::Rng;
+use common::solver::{Solution, SolveError};
+
+pub mod explore;
+
+/*
+ * A card per line, e.g. "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53": its winning numbers,
+ * then the numbers it has. Numbers are aligned in columns, so may be separated by several spaces.
+ */
+pub const GRAMMAR: Grammar = Grammar(&[Rule::Lines(&[
+    Token::Literal("Card"),
+    Token::Spaces,
+    Token::Number,
+    Token::Literal(":"),
+    Token::Spaces,
+    Token::Number,
+    Token::Many(&[Token::Spaces, Token::Number]),
+    Token::Spaces,
+    Token::Literal("|"),
+    Token::Spaces,
+    Token::Number,
+    Token::Many(&[Token::Spaces, Token::Number]),
+])]);
+
+/*
+ * Returns the card's numbers that are among its winning numbers, in order and without duplicates.
+ */
+pub fn matching_numbers(line: &[u8]) -> Vec<i32> {
+    line
+        .split(|&byte| byte == b':' || byte == b'|')
+        .skip(1)
+        .map(|part| -> Vec<i32> {
+            // Sorted without duplicates, to intersect as sets
+            let mut numbers: Vec<i32> = part
+                .split(|&byte| byte == b' ')
+                .filter_map(|s| str::from_utf8(s.trim_ascii()).ok()?.parse::<i32>().ok())
+                .collect();
+            numbers.sort_unstable();
+            numbers.dedup();
+            numbers
+        })
+        .reduce(|set1, set2| set1.into_iter().filter(|number| set2.binary_search(number).is_ok()).collect())
+        .unwrap()
+}
+
+/*
+ * Returns how many of a card's numbers are among its winning numbers.
+ */
+pub fn match_count(line: &[u8]) -> usize {
+    matching_numbers(line).len()
+}
+
+fn card_points(line: &[u8]) -> Result<Answer, Overflow> {
+    points(match_count(line))
+}
+
+/*
+ * The points a card with this many matches is worth.
+ */
+pub fn points(match_count: usize) -> Result<Answer, Overflow> {
+    match match_count {
+        0 => Ok(Answer::from(0u32)),
+        count => answer::pow2(count as u32 - 1, "doubling card points"),
+    }
+}
+
+/*
+ * Sums the points of all cards: 1 for the first match, doubled for each further match.
+ */
+pub fn sum_points(reader: impl BufRead) -> Result<Solution, SolveError> {
+    let lines = reader.lines().collect::<io::Result<Vec<String>>>()?;
+    sum_points_lines(lines)
+}
+
+/*
+ * As sum_points, for lines already in memory (e.g. borrowed from a MappedInput).
+ */
+pub fn sum_points_lines(lines: impl IntoIterator<Item = impl AsRef<[u8]>>) -> Result<Solution, SolveError> {
+    let points = lines
+        .into_iter()
+        .map(|line| card_points(line.as_ref()))
+        .collect::<Result<Vec<Answer>, Overflow>>()?;
+    let sum = answer::sum(points, "summing card points")?;
+
+    Ok(Solution::new(sum, Vec::new()))
+}
+
+/*
+ * Counts the cards held once every card has won copies of the cards after it.
+ */
+pub fn sum_cards(reader: impl BufRead) -> Result<Solution, SolveError> {
+    let match_counts: Vec<usize> = reader
+        .lines()
+        .map(|line_result| line_result.map(|line| match_count(line.as_bytes())))
+        .collect::<io::Result<_>>()?;
+    count_cards(match_counts)
+}
+
+/*
+ * As sum_cards, for lines already in memory (e.g. borrowed from a MappedInput).
+ */
+pub fn sum_cards_lines(lines: impl IntoIterator<Item = impl AsRef<[u8]>>) -> Result<Solution, SolveError> {
+    count_cards(lines.into_iter().map(|line| match_count(line.as_ref())).collect())
+}
+
+fn count_cards(match_counts: Vec<usize>) -> Result<Solution, SolveError> {
+    let counts = card_copies(&match_counts)?;
+    let diagnostics = counts.iter().enumerate().map(|(i, count)| format!("{}: {}", i, count)).collect();
+    let total_count = answer::sum(counts, "summing card copies")?;
+
+    Ok(Solution::new(total_count, diagnostics))
+}
+
+/*
+ * Returns how many copies of each card are held once every card has won copies of the cards after
+ * it, given each card's count of matches.
+ */
+pub fn card_copies(match_counts: &[usize]) -> Result<Vec<Answer>, SolveError> {
+    // Each card is held once, plus once for every copy of an earlier card that won it. Going
+    // forwards, card i's copies are final when it is reached, and each wins a copy of the next
+    // match_counts[i] cards (those past the last card are not won):
+    // 0->1                   = 1 (1, 2, 3, 4)
+    // 1->1 + 1               = 2 (2, 3)
+    // 2->1 + 1 + 2           = 4 (3, 4)
+    // A row of the table per card, of its copies
+    let cards = match_counts.len();
+    let mut copies = DpTable::new(cards, 1, Answer::from(1u32));
+    for (i, &count) in match_counts.iter().enumerate() {
+        checkpoint().map_err(|cancelled| {
+            SolveError::new(format!("{} (after counting copies of {} of {} cards)", cancelled, i, cards))
+        })?;
+        for j in (i + 1)..(i + 1 + count).min(cards) {
+            copies[(j, 0)] = answer::add(mem::take(&mut copies[(j, 0)]), Answer::clone(&copies[(i, 0)]), "counting card copies")?;
+        }
+    }
+    Ok((0..cards).map(|i| mem::take(&mut copies[(i, 0)])).collect())
+}
+
+/*
+ * Generates the number of cards given, numbered from first, each of 10 winning numbers and 25
+ * numbers it has, all below 100 and aligned in columns. Each card matches at most one number, so
+ * that the copies won grow no faster than the cards do, and any number of them can be counted.
+ */
+pub fn generate_cards(rng: &mut Rng, first: usize, cards: usize) -> String {
+    let mut text = String::new();
+    for id in first..first + cards {
+        // Distinct numbers, the first 10 winning and the next 25 had, bar one winning if it matches
+        let mut numbers: Vec<u64> = Vec::new();
+        while numbers.len() < 35 {
+            let number = rng.range(1..100);
+            if !numbers.contains(&number) {
+                numbers.push(number);
+            }
+        }
+        if rng.one_in(2) {
+            numbers[10 + rng.range(0..25) as usize] = numbers[rng.range(0..10) as usize];
+        }
+        write!(text, "Card {:>3}:", id).unwrap();
+        for (i, number) in numbers.iter().enumerate() {
+            write!(text, "{}{:>2}", if i == 10 { " | " } else { " " }, number).unwrap();
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod cards_tests {
+    use super::*;
+
+    #[test]
+    fn test_card_copies() {
+        let copies = |match_counts: &[usize]| card_copies(match_counts).unwrap().into_iter().map(|count| count.to_string()).collect::<Vec<_>>();
+        assert_eq!(copies(&[4, 2, 2, 1, 0, 0]), ["1", "2", "4", "8", "14", "1"]);
+        // Wins past the last card are not counted
+        assert_eq!(copies(&[3, 2]), ["1", "2"]);
+        assert!(copies(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_generate_cards() {
+        let text = generate_cards(&mut Rng::new(4), 1, 200);
+        assert!(GRAMMAR.validate(&text).is_empty());
+        assert!(text.lines().all(|line| match_count(line.as_bytes()) <= 1));
+        assert_eq!(sum_cards(text.as_bytes()).unwrap().answer, sum_cards_lines(text.lines()).unwrap().answer);
+    }
+}
@@ -0,0 +1,16 @@
+use std::io;
+use std::env;
+use common::input::MappedInput;
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().collect();
+    let file_path = &args[1];
+    let input = MappedInput::open(file_path)?;
+
+    let solution = day4::sum_cards_lines(input.lines())?;
+    solution.print_diagnostics();
+
+    println!("Sum: {}", solution.answer);
+
+    Ok(())
+}
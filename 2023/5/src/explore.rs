@@ -0,0 +1,126 @@
+use common::repl::{argument, Explorer};
+use common::solver::SolveError;
+
+use crate::{parse_almanac, ranges_succession_path, Almanac};
+
+const START_KEY: &str = "seed";
+const FINAL_KEY: &str = "location";
+
+pub struct AlmanacExplorer {
+    almanac: Almanac,
+}
+
+/*
+ * Parses the almanac once, to query its maps.
+ */
+pub fn explorer(input: &str) -> Result<Box<dyn Explorer>, SolveError> {
+    Ok(Box::new(AlmanacExplorer { almanac: parse_almanac(input)? }))
+}
+
+impl AlmanacExplorer {
+    fn seeds(&self) -> String {
+        self.almanac.seeds
+            .chunks_exact(2)
+            .map(|pair| format!("{}..{} ({} seeds)\n", pair[0], pair[0] + pair[1], pair[1]))
+            .collect()
+    }
+
+    fn maps(&self) -> String {
+        self.almanac.maps.bfs(START_KEY)
+            .iter()
+            .flat_map(|from| self.almanac.maps.edges_from(from).iter().map(move |(to, map)| (from, to, map)))
+            .map(|(from, to, map)| format!("{}-to-{} ({} ranges)\n", from, to, map.intervals().len()))
+            .collect()
+    }
+
+    fn map(&self, name: &str) -> Result<String, String> {
+        let (from, to) = name.split_once("-to-").ok_or_else(|| format!("{} is not a map name such as seed-to-soil", name))?;
+        let map = self.almanac.maps.edge(from, to).ok_or_else(|| format!("no map {}", name))?;
+        Ok(map.intervals()
+            .iter()
+            .enumerate()
+            .map(|(i, (range, value))| format!(
+                "{}: {}..{} -> {}..{} ({:+})\n",
+                i + 1, range.start, range.end, value, value + (range.end - range.start), value - range.start,
+            ))
+            .collect())
+    }
+
+    fn lookup(&self, category: &str, value: i64, to: &str) -> Result<String, String> {
+        let (categories, chain) = self.almanac.chain(category, to).ok_or_else(|| format!("no chain of maps from {} to {}", category, to))?;
+        let mut value = value;
+        let mut steps = vec![format!("{} {}", category, value)];
+        for (category, map) in categories.iter().skip(1).zip(chain) {
+            value = map.get(value).expect("unmapped values map to themselves");
+            steps.push(format!("{} {}", category, value));
+        }
+        Ok(steps.join(" -> "))
+    }
+
+    fn path(&self, seed: i64) -> Result<String, String> {
+        let (categories, chain) = self.almanac.chain(START_KEY, FINAL_KEY)
+            .ok_or_else(|| format!("no chain of maps from {} to {}", START_KEY, FINAL_KEY))?;
+        let (location, path) = ranges_succession_path(&chain, seed);
+        let intervals: Vec<String> = categories.windows(2)
+            .zip(path)
+            .map(|(pair, interval)| match interval {
+                0 => format!("{}-to-{} unmapped", pair[0], pair[1]),
+                interval => format!("{}-to-{} {}", pair[0], pair[1], interval),
+            })
+            .collect();
+        Ok(format!("{} {} -> {} {} through intervals: {}", START_KEY, seed, FINAL_KEY, location, intervals.join(", ")))
+    }
+}
+
+impl Explorer for AlmanacExplorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("seeds", "list the seed ranges"),
+            ("maps", "list the maps, from seed onwards"),
+            ("map FROM-to-TO", "list the intervals of a map, numbered as in paths"),
+            ("lookup CATEGORY VALUE [TO]", "follow a value through the maps to location (or TO)"),
+            ("path SEED", "show the interval taken through each map from seed to location"),
+        ]
+    }
+
+    fn command(&mut self, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("seeds", []) => Ok(self.seeds()),
+            ("maps", []) => Ok(self.maps()),
+            ("map", [name]) => self.map(name),
+            ("lookup", [category, _] | [category, _, _]) => {
+                self.lookup(category, argument(args, 1, "value")?, args.get(2).unwrap_or(&FINAL_KEY))
+            }
+            ("path", [_]) => self.path(argument(args, 0, "seed")?),
+            _ => Err(format!("unknown command {}, try help", [&[name], args].concat().join(" "))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod explore_tests {
+    use super::*;
+
+    #[test]
+    fn test_commands() {
+        let mut explorer = explorer(include_str!("../example_almanac.txt")).unwrap();
+        let mut run = |command: &str| {
+            let words: Vec<&str> = command.split(' ').collect();
+            explorer.command(words[0], &words[1..])
+        };
+        assert_eq!(run("seeds"), Ok("79..93 (14 seeds)\n55..68 (13 seeds)\n".to_string()));
+        assert_eq!(run("maps").unwrap().lines().next(), Some("seed-to-soil (2 ranges)"));
+        assert_eq!(run("map seed-to-soil"), Ok("1: 98..100 -> 50..52 (-48)\n2: 50..98 -> 52..100 (+2)\n".to_string()));
+        assert_eq!(
+            run("lookup seed 79"),
+            Ok("seed 79 -> soil 81 -> fertilizer 81 -> water 81 -> light 74 -> temperature 78 -> humidity 78 -> location 82".to_string()),
+        );
+        assert_eq!(run("lookup light 77 temperature"), Ok("light 77 -> temperature 45".to_string()));
+        assert_eq!(run("path 79").unwrap(), "seed 79 -> location 82 through intervals: seed-to-soil 2, \
+soil-to-fertilizer unmapped, fertilizer-to-water unmapped, water-to-light 2, light-to-temperature 3, \
+temperature-to-humidity unmapped, humidity-to-location 1");
+        assert_eq!(run("map soil-to-seed"), Err("no map soil-to-seed".to_string()));
+        assert_eq!(run("lookup seed x"), Err("x is not a valid value".to_string()));
+        assert_eq!(run("card 3"), Err("unknown command card 3, try help".to_string()));
+    }
+}
@@ -0,0 +1,479 @@
+use std::ops::Range;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt::Write;
+use common::graph::Graph;
+use common::budget::checkpoint;
+use common::frames::{Colour, Frame, FrameSink};
+use common::grammar::{Grammar, Rule, Token};
+use common::random::Rng;
+use common::solver::{Solution, SolveError};
+
+pub mod explore;
+
+type StringStringsMap = HashMap<String, Vec<String>>;
+
+/*
+ * An almanac: the seed ranges, then sections each holding a map from one category to another, with
+ * a line per range of "destination start, source start, length".
+ */
+pub const GRAMMAR: Grammar = Grammar(&[
+    Rule::Line(&[Token::Literal("seeds:"), Token::Spaces, Token::Number, Token::Many(&[Token::Spaces, Token::Number])]),
+    Rule::Blank,
+    Rule::Sections(&[
+        Rule::Line(&[Token::Word, Token::Literal("-to-"), Token::Word, Token::Literal(" map:")]),
+        Rule::Lines(&[Token::Number, Token::Spaces, Token::Number, Token::Spaces, Token::Number]),
+    ]),
+]);
+
+/*
+ * Parse a "key values config" file
+ *
+ * Given an input string, which should contain newlines, this function looks for mappings of a key,
+ * delimited by a colon, to values. The values can be spread over several lines, but are marked as
+ * ending for that key by a blank line.
+ *
+ * Returns a HashMap mapping each key to its values array.
+ */
+pub fn parse_key_values_config(input: &str) -> StringStringsMap {
+    let mut result = HashMap::new();
+    let regex_pattern = r"(?m)^([^:\n]+):\s*([^:\n]+(?:\s*[^:\n]+)*)$";
+    let regex = Regex::new(regex_pattern).unwrap();
+
+    for capture in regex.captures_iter(input) {
+        let key = capture[1].trim().to_string();
+        let values = capture[2]
+            .split('\n')
+            .map(|v| v.trim().to_string())
+            .collect::<Vec<_>>();
+        result.insert(key, values);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod parse_key_values_config_tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_key_values_config() {
+        let input = indoc! {"
+            key0: value0
+
+            key1:
+            value1
+            value2
+
+            key2:
+            value3
+        "};
+
+        let expected_result = StringStringsMap::from([
+            ("key0".to_string(), vec!{"value0".to_string()}),
+            ("key1".to_string(), vec!{"value1".to_string(), "value2".to_string()}),
+            ("key2".to_string(), vec!{"value3".to_string()}),
+        ]);
+        let result = parse_key_values_config(input);
+
+        assert_eq!(result, expected_result);
+    }
+}
+
+
+#[derive(Debug, Default)]
+pub struct OffsetIntervalMap {
+    intervals: Vec<(Range<i64>, i64)>,
+}
+
+impl OffsetIntervalMap {
+    pub fn new() -> Self {
+        OffsetIntervalMap { intervals: Vec::new() }
+    }
+
+    pub fn insert(&mut self, range: Range<i64>, value: i64) {
+        self.intervals.push((range, value));
+    }
+
+    /*
+     * The intervals inserted, in order, each with the value its start maps to.
+     */
+    pub fn intervals(&self) -> &[(Range<i64>, i64)] {
+        &self.intervals
+    }
+
+    pub fn get(&self, key: i64) -> Option<i64> {
+        if let Some((range, value)) = self.intervals.iter().rev().find(|(r, _)| r.contains(&key)) {
+            Some(value + (key - range.start))
+        } else {
+            Some(key)
+        }
+    }
+
+    pub fn get_with_interval(&self, key: i64) -> Option<(i64, usize)> {
+        if let Some((index, (range, value))) = self.intervals.iter().enumerate().rev().find(|(_, (r, _))| r.contains(&key)) {
+            // One-based index (sorry Dijkstra)...
+            Some((value + (key - range.start), index + 1))
+        } else {
+            // ...because we'll use 0 as the id to indicate no range was matched
+            Some((key, 0))
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod offset_interval_map_tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_interval_map() {
+        let mut offset_map = OffsetIntervalMap::new();
+
+        // Add test mappings
+        offset_map.insert(0..5, 10); // which will get id 1
+        offset_map.insert(20..30, 50); //which will get id 2
+
+        // Test entries within the ranges
+        assert_eq!(offset_map.get(3), Some(13));
+        assert_eq!(offset_map.get(25), Some(55));
+
+        // Test an entry outside the example ranges (using the key itself as default)
+        assert_eq!(offset_map.get(40), Some(40));
+
+        // Test entries within the ranges using get_with_interval, returning the interval id
+        assert_eq!(offset_map.get_with_interval(3), Some((13, 1)));
+        assert_eq!(offset_map.get_with_interval(25), Some((55, 2)));
+
+        // Test an entry outside the example ranges
+        // which is expecting to return the key itself as default with interval id 0
+        assert_eq!(offset_map.get_with_interval(40), Some((40, 0)));
+
+        // Mapping near the top of the range of i64 must not overflow on the way
+        let mut high_map = OffsetIntervalMap::new();
+        high_map.insert(i64::MAX - 10..i64::MAX, i64::MAX - 5);
+        assert_eq!(high_map.get(i64::MAX - 6), Some(i64::MAX - 1));
+        assert_eq!(high_map.get_with_interval(i64::MAX - 10), Some((i64::MAX - 5, 1)));
+    }
+}
+
+/*
+ * Computes final successor value and path of interval ids taken to reach it via interval maps given.
+ *
+ * The interval maps are the edges of the category chain, in order, e.g. as found by walking the
+ * category graph from seed to location.
+ */
+pub fn ranges_succession_path(chain: &[&OffsetIntervalMap], start_value: i64) -> (i64, Vec<i64>) {
+    let mut path = Vec::new();
+    let mut current_value = start_value;
+
+    for offset_map in chain {
+        match offset_map.get_with_interval(current_value) {
+            Some((next_value, index)) => {
+                path.push(index as i64);
+                current_value = next_value;
+            }
+            None => break,
+        }
+    }
+    (current_value, path)
+}
+
+
+/*
+ * Performs binary search to find first value in the range start to end where is_equal(value)
+ * evaluates to False, on the assumption that after that all remaining values in the range
+ * also evaluate to False. Apart from that condition the range does not otherwise have to be
+ * ordered.
+ */
+pub fn binary_search_first_not_equal<F>(start: i64, end: i64, mut is_equal: F) -> i64
+where
+    F: FnMut(i64) -> bool
+{
+    let mut low = start;
+    let mut high = end;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match is_equal(mid) {
+            true => low = mid + 1,
+            false => high = mid
+        }
+    }
+
+    low
+}
+
+#[cfg(test)]
+mod binary_search_tests {
+    use super::binary_search_first_not_equal;
+
+    #[test]
+    fn test_binary_search_first_not_equal() {
+        // Test case: Searching for the first element not equal to 2
+        let values = vec![2, 2, 2, 2, 2, 3, 3, 3, 4, 5];
+        let is_equal = |x: i64| values[x as usize] == 2;
+        let result = binary_search_first_not_equal(0, values.len() as i64, is_equal);
+        assert_eq!(result, 5);
+    }
+}
+
+/*
+ * An almanac parsed: the numbers on the seeds line (pairs of start and length of seed ranges), and
+ * the maps from each category to the next.
+ */
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<i64>,
+    pub maps: Graph<String, OffsetIntervalMap>,
+}
+
+impl Almanac {
+    /*
+     * The maps to apply in turn to take a value in one category to the other, and the categories
+     * passed through (from and to included).
+     */
+    pub fn chain(&self, from: &str, to: &str) -> Option<(Vec<String>, Vec<&OffsetIntervalMap>)> {
+        let categories = self.maps.bfs_path(from, to)?;
+        let chain = self.maps.path_edges(&categories).expect("path follows edges");
+        Some((categories, chain))
+    }
+}
+
+/*
+ * The numbers in a line of the almanac, separated by whitespace.
+ */
+fn parse_numbers(line: &str) -> Result<Vec<i64>, SolveError> {
+    line.split_whitespace()
+        .map(|number| number.parse().map_err(|error| SolveError::new(format!("{} in {:?}: {}", number, line, error))))
+        .collect()
+}
+
+/*
+ * Parses an almanac into its seeds and the graph of maps between categories.
+ */
+pub fn parse_almanac(content: &str) -> Result<Almanac, SolveError> {
+    let key_values = parse_key_values_config(content);
+
+    let mut graph: Graph<String, OffsetIntervalMap> = Graph::new();
+    let mut start_values: Vec<i64> = Vec::new();
+
+    for (key, values) in key_values.iter() {
+        // Define a regex pattern for extracting map names
+        let regex_pattern = r"^(?P<from_map>[^\s]+)-to-(?P<to_map>[^\s]+)\s+map$";
+        let regex = Regex::new(regex_pattern).unwrap();
+        if let Some(captures) = regex.captures(key) {
+            if let (Some(from_map), Some(to_map)) = (captures.name("from_map"), captures.name("to_map")) {
+                let from_map_string: String = from_map.as_str().to_string();
+                let to_map_string: String = to_map.as_str().to_string();
+                let mut offset_map: OffsetIntervalMap = OffsetIntervalMap::new();
+
+                // Have the keys, now handle the values
+                for line in values.iter().flat_map(|s| s.split('\n')) {
+                    checkpoint().map_err(|cancelled| {
+                        SolveError::new(format!("{} (while reading the {})", cancelled, key))
+                    })?;
+                    let [start1, start2, count] = parse_numbers(line)?[..] else {
+                        return Err(SolveError::new(format!("expected a destination start, source start and length: {}", line)));
+                    };
+                    // Both source and destination ranges must fit, so that offsets applied
+                    // within them cannot overflow either
+                    let (Some(end2), Some(_)) = (start2.checked_add(count), start1.checked_add(count)) else {
+                        return Err(SolveError::new(format!("overflow in range: {}", line)));
+                    };
+                    // Store range mapping:
+                    offset_map.insert(start2..end2, start1);
+                }
+
+                graph.add_edge(from_map_string, to_map_string, offset_map);
+            }
+        } else {
+            // It is the line with the start values (the seeds)
+            start_values = parse_numbers(&values.join(" "))?;
+        }
+    }
+
+    Ok(Almanac { seeds: start_values, maps: graph })
+}
+
+/*
+ * Finds the lowest location number for any seed in the ranges of seeds given by the almanac.
+ *
+ * Emits a frame into frames for each segment of a seed range found to share the same path of
+ * intervals through the maps.
+ */
+pub fn map_seeds(content: &str, frames: &mut dyn FrameSink) -> Result<Solution, SolveError> {
+    let almanac = parse_almanac(content)?;
+    let start_values = &almanac.seeds;
+    // 'seeds' is different to 'seed', so the key of the seeds line cannot be used, and the
+    // category has to be hardcoded
+    const START_KEY: &str = "seed";
+
+    const REQUIRED_FINAL_KEY: &str = "location";
+
+    let (_, chain) = almanac.chain(START_KEY, REQUIRED_FINAL_KEY).ok_or_else(|| {
+        SolveError::new(format!("No chain of maps from {} to {}.", START_KEY, REQUIRED_FINAL_KEY))
+    })?;
+
+    if start_values.chunks_exact(2).any(|pair| pair[0].checked_add(pair[1]).is_none()) {
+        return Err(SolveError::new("overflow in seed range"));
+    }
+
+    // One row per seed range, divided into the segments found to share a path of intervals
+    const SPLIT_FRAME_WIDTH: usize = 64;
+    let mut diagnostics = Vec::new();
+    let mut splits = Frame::new(SPLIT_FRAME_WIDTH, start_values.len() / 2);
+
+    // Lowest location found so far, reported as the progress made if the run is cancelled
+    let mut lowest: Option<i64> = None;
+    let seed_ranges = start_values.len() / 2;
+
+    let result: Option<i64> = start_values
+        .chunks_exact(2)
+        .enumerate()
+        .map(|(row, pair)| -> Result<Option<i64>, SolveError> {
+            if let [start, count] = pair {
+                diagnostics.push(format!("{} {}", start, count));
+                let range = *start..(*start + *count);
+                let mut results = Vec::new();
+
+                let mut current_start = range.start;
+                while current_start < range.end {
+                    checkpoint().map_err(|cancelled| SolveError::new(format!(
+                        "{} (lowest location so far {}, after {} of {} seed ranges and {} segments of the next)",
+                        cancelled,
+                        lowest.map_or("none".to_string(), |value| value.to_string()),
+                        row,
+                        seed_ranges,
+                        results.len(),
+                    )))?;
+                    let (final_value, current_path) = ranges_succession_path(&chain, current_start);
+                    results.push(final_value);
+                    lowest = Some(lowest.map_or(final_value, |lowest| lowest.min(final_value)));
+
+                    let segment_start = current_start;
+                    current_start = binary_search_first_not_equal(current_start, range.end, |element| {
+                        let (_, path) = ranges_succession_path(&chain, element);
+                        path == current_path
+                    });
+
+                    if frames.enabled() {
+                        let x = |value: i64| ((value - start) as i128 * SPLIT_FRAME_WIDTH as i128 / *count as i128) as usize;
+                        let colour = Colour::CYCLE[(results.len() - 1) % Colour::CYCLE.len()];
+                        for column in x(segment_start)..x(current_start).max(x(segment_start) + 1) {
+                            splits.set(column, row, '#', colour);
+                        }
+                        frames.emit(splits.clone().with_caption(format!(
+                            "seeds {}..{}: {}..{} -> location {}",
+                            range.start, range.end, segment_start, current_start, final_value
+                        )));
+                    }
+                }
+
+                Ok(results.into_iter().min())
+            } else {
+                Ok(None)
+            }
+        })
+        .collect::<Result<Vec<Option<i64>>, SolveError>>()?
+        .into_iter()
+        .flatten()
+        .min();
+
+    match result {
+        Some(value) => Ok(Solution::new(value, diagnostics)),
+        None => Err(SolveError::new("Lookup failed.")),
+    }
+}
+
+// Seeds mapped between checks of the run's budget
+const SEEDS_PER_CHECK: i64 = 1 << 16;
+
+/*
+ * As map_seeds, but mapping every seed in turn: far too slow for real almanacs, but simple enough
+ * to check map_seeds against. Empty ranges of seeds or in maps are rejected, as puzzle inputs never
+ * have them.
+ */
+pub fn map_seeds_by_trial(content: &str) -> Result<Solution, SolveError> {
+    let almanac = parse_almanac(content)?;
+    let (_, chain) = almanac.chain("seed", "location").ok_or_else(|| {
+        SolveError::new("No chain of maps from seed to location.")
+    })?;
+    if let Some((range, _)) = chain.iter().flat_map(|map| map.intervals()).find(|(range, _)| range.is_empty()) {
+        return Err(SolveError::new(format!("empty range in map at {}", range.start)));
+    }
+
+    let mut lowest: Option<i64> = None;
+    for pair in almanac.seeds.chunks_exact(2) {
+        if pair[1] == 0 {
+            return Err(SolveError::new(format!("empty seed range at {}", pair[0])));
+        }
+        let end = pair[0].checked_add(pair[1]).ok_or_else(|| SolveError::new("overflow in seed range"))?;
+        for seed in pair[0]..end {
+            if (seed - pair[0]) % SEEDS_PER_CHECK == 0 {
+                checkpoint()?;
+            }
+            let (location, _) = ranges_succession_path(&chain, seed);
+            lowest = Some(lowest.map_or(location, |lowest| lowest.min(location)));
+        }
+    }
+
+    match lowest {
+        Some(value) => Ok(Solution::new(value, Vec::new())),
+        None => Err(SolveError::new("Lookup failed.")),
+    }
+}
+
+/*
+ * Generates an almanac for checking map_seeds against map_seeds_by_trial: up to size seed ranges,
+ * and maps through the usual categories of up to size ranges each, with every number below
+ * 10 * size. The source ranges of a map never overlap, as in real almanacs.
+ */
+pub fn generate_almanac(rng: &mut Rng, size: usize) -> String {
+    const CATEGORIES: [&str; 8] = ["seed", "soil", "fertilizer", "water", "light", "temperature", "humidity", "location"];
+    let size = size.max(1) as u64;
+    let limit = 10 * size;
+
+    let mut almanac = String::from("seeds:");
+    for _ in 0..rng.range(1..size + 1) {
+        let start = rng.range(0..limit);
+        write!(almanac, " {} {}", start, rng.range(1..limit - start + 1)).unwrap();
+    }
+    almanac.push('\n');
+
+    for pair in CATEGORIES.windows(2) {
+        write!(almanac, "\n{}-to-{} map:\n", pair[0], pair[1]).unwrap();
+        // Bounds of the source ranges, in order, so that they do not overlap
+        let mut bounds: Vec<u64> = (0..2 * rng.range(1..size + 1)).map(|_| rng.range(0..limit)).collect();
+        bounds.sort();
+        bounds.dedup();
+        if bounds.len() < 2 {
+            bounds = vec![bounds[0], bounds[0] + 1];
+        }
+        for source in bounds.chunks_exact(2) {
+            writeln!(almanac, "{} {} {}", rng.range(0..limit), source[0], source[1] - source[0]).unwrap();
+        }
+    }
+    almanac
+}
+
+#[cfg(test)]
+mod by_trial_tests {
+    use super::*;
+
+    #[test]
+    fn test_map_seeds_by_trial() {
+        let solution = map_seeds_by_trial(include_str!("../example_almanac.txt")).unwrap();
+        assert_eq!(solution.answer, "46");
+    }
+
+    #[test]
+    fn test_malformed_almanac_fails() {
+        let example = include_str!("../example_almanac.txt");
+        let short_range = example.replacen("50 98 2", "50 98", 1);
+        assert!(parse_almanac(&short_range).unwrap_err().to_string().contains("50 98"));
+        assert!(parse_almanac(&example.replacen("79 14", "79 x4", 1)).is_err());
+        assert!(parse_almanac(&example.replacen("50 98 2", "50 98 99999999999999999999", 1)).is_err());
+    }
+}
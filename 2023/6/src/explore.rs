@@ -0,0 +1,64 @@
+use common::repl::{argument, Explorer};
+use common::solver::SolveError;
+
+use crate::{count_ways, numbers_from_string, solve_quadratic};
+
+pub struct RacesExplorer {
+    races: Vec<(u64, u64)>,
+}
+
+/*
+ * Reads the race times and record distances once, to look at each race.
+ */
+pub fn explorer(input: &str) -> Result<Box<dyn Explorer>, SolveError> {
+    let mut lines = input.lines();
+    let (Some(times), Some(distances)) = (lines.next(), lines.next()) else {
+        return Err(SolveError::new("expected lines of times and distances"));
+    };
+    let races = numbers_from_string(times).into_iter().zip(numbers_from_string(distances)).collect();
+    Ok(Box::new(RacesExplorer { races }))
+}
+
+impl RacesExplorer {
+    fn race(&self, number: usize) -> Result<String, String> {
+        let &(time, distance) = number.checked_sub(1)
+            .and_then(|index| self.races.get(index))
+            .ok_or_else(|| format!("no race {} (there are {})", number, self.races.len()))?;
+        let (discriminant, solution0, solution1) = solve_quadratic(time, distance);
+        let ways = count_ways(time, distance);
+        Ok(format!(
+            "time {}, record {}: discriminant {}, holding the button between {:.3} and {:.3} beats it, {} ways",
+            time, distance, discriminant, solution0, solution1, ways,
+        ))
+    }
+}
+
+impl Explorer for RacesExplorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[("race N", "show race N's time and record, the solutions of its quadratic and the ways to win")]
+    }
+
+    fn command(&mut self, name: &str, args: &[&str]) -> Result<String, String> {
+        match (name, args) {
+            ("race", [_]) => self.race(argument(args, 0, "race number")?),
+            _ => Err(format!("unknown command {}, try help", [&[name], args].concat().join(" "))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod explore_tests {
+    use super::*;
+
+    #[test]
+    fn test_race() {
+        let mut explorer = explorer("Time:      7  15   30\nDistance:  9  40  200\n").unwrap();
+        assert_eq!(
+            explorer.command("race", &["3"]),
+            Ok("time 30, record 200: discriminant 100, holding the button between 10.000 and 20.000 beats it, 9 ways".to_string()),
+        );
+        assert_eq!(explorer.command("race", &["1"]).unwrap().rsplit(", ").next(), Some("4 ways"));
+        assert_eq!(explorer.command("race", &["4"]), Err("no race 4 (there are 3)".to_string()));
+        assert!(super::explorer("Time: 7\n").is_err());
+    }
+}
@@ -0,0 +1,240 @@
+use std::io::{self, BufRead};
+use common::answer::{self, Answer};
+use common::budget::{checkpoint, Cancelled};
+use common::grammar::{Grammar, Rule, Token};
+use common::random::Rng;
+use common::solver::{Solution, SolveError};
+
+pub mod explore;
+
+/*
+ * The race times on one line and record distances on the next, aligned in columns.
+ */
+pub const GRAMMAR: Grammar = Grammar(&[
+    Rule::Line(&[Token::Literal("Time:"), Token::Spaces, Token::Number, Token::Many(&[Token::Spaces, Token::Number])]),
+    Rule::Line(&[Token::Literal("Distance:"), Token::Spaces, Token::Number, Token::Many(&[Token::Spaces, Token::Number])]),
+]);
+
+/*
+ * Returns the numbers in a line of the race records, ignoring its label.
+ */
+pub fn numbers_from_string(string: &str) -> Vec<u64> {
+    string.split_whitespace()
+          .filter_map(|s| s.parse().ok())
+          .collect()
+}
+
+/*
+ * Returns the discriminant and both solutions of the quadratic equation described for count_ways.
+ * The smaller is worked out as 2s / (T + sqrt(T^2 - 4s)), which is the same, so as not to take two
+ * nearly equal numbers from each other when the record is short for the race.
+ */
+fn solve_quadratic(time: u64, distance: u64) -> (f64, f64, f64) {
+    let ftime = time as f64;
+    let discriminant = ftime*ftime - 4.0 * (distance as f64);
+    let sqrt = discriminant.sqrt();
+    let solution0 = 2.0 * (distance as f64) / (ftime + sqrt);
+    let solution1 = (ftime + sqrt)/2.0;
+    (discriminant, solution0, solution1)
+}
+
+/*
+ * Returns exclusive count of all integers between solutions of inequality equation.
+ *
+ * Equation of interest is:
+ * s < Tt - t^2
+ * where s is the "distance" that needs to be exceeded, t is the time spent increasing speed, and T is
+ * total "time" both doing that and moving.
+ * Using the conventional expression of a quadratic equation for t:
+ * t^2 - Tt + s < 0
+ * Solving for t:
+ * t = ( T +- sqrt(T^2 - 4s) ) / 2
+ * We want to count all the integer solutions between these two solutions.  i.e. find the count of
+ * all integer values of t that fulfil the inequality.
+ *
+ * The solutions are only as exact as an f64 (and times past 2^53 are rounded before anything is
+ * worked out), so the first winning t is found from the smaller by checking the integers either
+ * side of it in u128. The winning t are symmetric about T / 2.
+ */
+pub fn count_ways(time: u64, distance: u64) -> u64 {
+    let (_, solution0, _) = solve_quadratic(time, distance);
+    let beats = |held: u64| held as u128 * (time - held) as u128 > distance as u128;
+    // With no solutions (or none an f64 can tell apart), from the middle, as the best chance
+    let mut first = if solution0.is_nan() { time / 2 } else { (solution0 as u64).min(time / 2) };
+    while first > 0 && beats(first - 1) {
+        first -= 1;
+    }
+    while first <= time / 2 && !beats(first) {
+        first += 1;
+    }
+    if first > time / 2 { 0 } else { time - 2 * first + 1 }
+}
+
+/*
+ * Multiplies together the number of ways to win each race, given times on the first line and
+ * record distances on the second.
+ */
+pub fn product_of_ways_count(reader: impl BufRead) -> Result<Solution, SolveError> {
+    let (times, distances) = read_races(reader)?;
+    let mut diagnostics = Vec::new();
+
+    let counts = times.into_iter().zip(distances).map(|(time, distance)| {
+        checkpoint()?;
+        let (discriminant, solution0, solution1) = solve_quadratic(time, distance);
+        let count = count_ways(time, distance);
+        diagnostics.push(format!("{} {} {} {} {} {}", time, distance, discriminant, solution0, solution1, count));
+        Ok(Answer::from(count))
+    }).collect::<Result<Vec<Answer>, Cancelled>>()?;
+    let product = answer::product(counts, "multiplying counts of ways")?;
+
+    Ok(Solution::new(product, diagnostics))
+}
+
+/*
+ * Reads the race times from the first line and the record distances from the second.
+ */
+fn read_races(reader: impl BufRead) -> Result<(Vec<u64>, Vec<u64>), SolveError> {
+    let lines = reader.lines().collect::<io::Result<Vec<String>>>()?;
+    match &lines[..] {
+        [times, distances, ..] => Ok((numbers_from_string(times), numbers_from_string(distances))),
+        _ => Err(SolveError::new("expected lines of times and distances")),
+    }
+}
+
+/*
+ * The ways to beat the record in a race, worked out exactly in integers: holding the button for
+ * held goes held * (time - held), which beats the record between the roots of
+ * held^2 - time * held + distance = 0. The integer square root of the discriminant puts the lower
+ * root within a step of the first hold that wins, found by trying the holds either side of it,
+ * and the holds that win are symmetric about time / 2.
+ */
+fn ways_by_trial(time: u64, distance: u64) -> u64 {
+    // In u128, where time * time and 4 * distance cannot overflow
+    let (time, distance) = (time as u128, distance as u128);
+    let goes = |held: u128| held * (time - held);
+    let Some(discriminant) = (time * time).checked_sub(4 * distance) else {
+        return 0;
+    };
+    let mut first = (time - discriminant.isqrt()) / 2;
+    while first > 0 && goes(first - 1) > distance {
+        first -= 1;
+    }
+    while first <= time / 2 && goes(first) <= distance {
+        first += 1;
+    }
+    if first > time / 2 { 0 } else { (time - 2 * first + 1) as u64 }
+}
+
+/*
+ * As product_of_ways_count, but without floating point: each race's ways are found by trying the
+ * holds either side of its roots, simple enough to check count_ways against. Records that cannot
+ * be beaten are rejected, as puzzle inputs never have them.
+ */
+pub fn product_of_ways_by_trial(reader: impl BufRead) -> Result<Solution, SolveError> {
+    let (times, distances) = read_races(reader)?;
+    let mut product = Answer::from(1u64);
+    for (time, distance) in times.into_iter().zip(distances) {
+        checkpoint()?;
+        let ways = ways_by_trial(time, distance);
+        if ways == 0 {
+            return Err(SolveError::new(format!("the record of {} cannot be beaten in {}", distance, time)));
+        }
+        product = answer::mul(product, Answer::from(ways), "multiplying counts of ways")?;
+    }
+    Ok(Solution::new(product, Vec::new()))
+}
+
+/*
+ * Generates race records for checking count_ways against product_of_ways_by_trial, each with a
+ * record that can be beaten: either up to four races of times up to 10 * size, or one long race,
+ * of up to 2^62 milliseconds, past the integers an f64 holds exactly. A third of the records are
+ * set by holding the button for a whole number of milliseconds, which puts the roots on integers,
+ * and a third fall just short of the best, which puts them close together.
+ */
+pub fn generate_races(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1) as u64;
+    let times: Vec<u64> = if rng.one_in(3) {
+        let bits = rng.range(20..63);
+        vec![rng.range(1 << (bits - 1)..1 << bits)]
+    } else {
+        (0..rng.range(1..5)).map(|_| rng.range(2..10 * size + 2)).collect()
+    };
+    let races: Vec<(u64, u64)> = times
+        .into_iter()
+        .map(|time| {
+            // Holding for half the time goes furthest, past u64::MAX in the longest races
+            let best = u64::try_from((time as u128 / 2) * (time as u128 - time as u128 / 2)).unwrap_or(u64::MAX);
+            let distance = match rng.range(0..3) {
+                0 => {
+                    let held = rng.range(0..(time / 2).min(u64::MAX / time));
+                    held * (time - held)
+                }
+                1 => best - rng.range(1..best.min(1000) + 1),
+                _ => rng.range(0..best),
+            };
+            (time, distance)
+        })
+        .collect();
+
+    let columns = |numbers: Vec<u64>| -> String {
+        let width = races.iter().map(|&(time, distance)| time.max(distance).to_string().len()).max().unwrap_or(1);
+        numbers.iter().map(|number| format!("  {:>width$}", number, width = width)).collect()
+    };
+    format!(
+        "Time:    {}\nDistance:{}\n",
+        columns(races.iter().map(|&(time, _)| time).collect()),
+        columns(races.iter().map(|&(_, distance)| distance).collect()),
+    )
+}
+
+#[cfg(test)]
+mod races_tests {
+    use super::*;
+
+    #[test]
+    fn test_unreadable_line_fails() {
+        let races = b"Time:      7  15   30\nDistance:  9  40  200\n";
+        assert_eq!(product_of_ways_count(&races[..]).unwrap().answer, "288");
+        assert_eq!(product_of_ways_by_trial(&races[..]).unwrap().answer, "288");
+
+        let unreadable = b"Time:      7  15   30\nDistance:  9  40  20\xff\n";
+        assert!(product_of_ways_count(&unreadable[..]).is_err());
+        assert!(product_of_ways_by_trial(&unreadable[..]).is_err());
+        assert!(product_of_ways_count(&b"Time: 7\n"[..]).is_err());
+    }
+
+    #[test]
+    fn test_ways_by_trial() {
+        for time in 0..60 {
+            for distance in 0..(time * time / 4 + 2) {
+                let ways = (0..=time).filter(|held| held * (time - held) > distance).count() as u64;
+                assert_eq!(ways_by_trial(time, distance), ways, "{} {}", time, distance);
+                assert_eq!(count_ways(time, distance), ways, "{} {}", time, distance);
+            }
+        }
+        // Far past an f64's integers, with the roots on integers and close together
+        for (time, distance, ways) in [
+            (u64::MAX, 0, u64::MAX - 1),
+            (1 << 32, (1 << 62) - 1, 1),
+            ((1 << 33) + 1, 3 * (1 << 33) - 4, (1 << 33) - 6),
+            ((1 << 53) + 1, 1 << 53, (1 << 53) - 2),
+        ] {
+            assert_eq!(ways_by_trial(time, distance), ways, "{} {}", time, distance);
+            assert_eq!(count_ways(time, distance), ways, "{} {}", time, distance);
+        }
+    }
+
+    #[test]
+    fn test_generated_races() {
+        for seed in 0..300 {
+            let races = generate_races(&mut Rng::new(seed), 20);
+            assert!(GRAMMAR.validate(&races).is_empty(), "{}", races);
+            assert_eq!(
+                product_of_ways_count(races.as_bytes()).unwrap().answer,
+                product_of_ways_by_trial(races.as_bytes()).unwrap().answer,
+                "{}",
+                races,
+            );
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use std::fs::File;
+use std::io;
+use std::env;
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().collect();
+    let file_path = &args[1];
+    let file = File::open(file_path)?;
+    let reader = io::BufReader::new(file);
+
+    let solution = day6::product_of_ways_count(reader)?;
+    solution.print_diagnostics();
+
+    println!("Sum: {}", solution.answer);
+
+    Ok(())
+}
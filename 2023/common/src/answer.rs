@@ -0,0 +1,117 @@
+use std::fmt;
+use std::io;
+
+/*
+ * Integer type in which answers are accumulated.
+ *
+ * By default this is a u64, and every operation below is checked so that a result too large to
+ * represent fails with an Overflow error instead of silently wrapping. Building with the bigint
+ * feature makes it an arbitrary precision integer instead, for inputs whose answers do not fit.
+ */
+#[cfg(not(feature = "bigint"))]
+pub type Answer = u64;
+
+#[cfg(feature = "bigint")]
+pub type Answer = num_bigint::BigUint;
+
+/*
+ * Error for an answer that does not fit in Answer. Holds a description of what was being computed.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow(pub String);
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "overflow while {} (try building with --features bigint)", self.0)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+impl From<Overflow> for io::Error {
+    fn from(overflow: Overflow) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, overflow)
+    }
+}
+
+#[cfg(not(feature = "bigint"))]
+pub fn add(a: Answer, b: Answer, context: &str) -> Result<Answer, Overflow> {
+    a.checked_add(b).ok_or_else(|| Overflow(context.to_string()))
+}
+
+#[cfg(feature = "bigint")]
+pub fn add(a: Answer, b: Answer, _context: &str) -> Result<Answer, Overflow> {
+    Ok(a + b)
+}
+
+#[cfg(not(feature = "bigint"))]
+pub fn mul(a: Answer, b: Answer, context: &str) -> Result<Answer, Overflow> {
+    a.checked_mul(b).ok_or_else(|| Overflow(context.to_string()))
+}
+
+#[cfg(feature = "bigint")]
+pub fn mul(a: Answer, b: Answer, _context: &str) -> Result<Answer, Overflow> {
+    Ok(a * b)
+}
+
+/*
+ * Returns 2 raised to the power given.
+ */
+#[cfg(not(feature = "bigint"))]
+pub fn pow2(exponent: u32, context: &str) -> Result<Answer, Overflow> {
+    Answer::from(1u32).checked_shl(exponent).ok_or_else(|| Overflow(context.to_string()))
+}
+
+#[cfg(feature = "bigint")]
+pub fn pow2(exponent: u32, _context: &str) -> Result<Answer, Overflow> {
+    Ok(Answer::from(1u32) << exponent)
+}
+
+/*
+ * Evaluates a string of decimal digits.
+ */
+pub fn parse_digits(digits: &str, context: &str) -> Result<Answer, Overflow> {
+    digits.parse().map_err(|_| Overflow(context.to_string()))
+}
+
+pub fn sum<I: IntoIterator<Item = Answer>>(values: I, context: &str) -> Result<Answer, Overflow> {
+    values.into_iter().try_fold(Answer::from(0u32), |acc, value| add(acc, value, context))
+}
+
+pub fn product<I: IntoIterator<Item = Answer>>(values: I, context: &str) -> Result<Answer, Overflow> {
+    values.into_iter().try_fold(Answer::from(1u32), |acc, value| mul(acc, value, context))
+}
+
+#[cfg(test)]
+mod answer_tests {
+    use super::*;
+
+    #[test]
+    fn test_small_answers() {
+        let values = [3u32, 4, 5].map(Answer::from);
+        assert_eq!(sum(values.iter().cloned(), "summing"), Ok(Answer::from(12u32)));
+        assert_eq!(product(values, "multiplying"), Ok(Answer::from(60u32)));
+        assert_eq!(pow2(10, "doubling"), Ok(Answer::from(1024u32)));
+        assert_eq!(parse_digits("467", "parsing"), Ok(Answer::from(467u32)));
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    #[test]
+    fn test_overflow_is_an_error() {
+        let error = Overflow("summing card points".to_string());
+        assert_eq!(sum([u64::MAX, 1], "summing card points"), Err(error));
+        assert_eq!(product([1 << 32, 1 << 32], "multiplying"), Err(Overflow("multiplying".to_string())));
+        assert_eq!(pow2(63, "doubling"), Ok(1 << 63));
+        assert!(pow2(64, "doubling").is_err());
+        assert!(parse_digits("18446744073709551616", "parsing").is_err());
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_big_answers() {
+        let big = pow2(64, "doubling").unwrap();
+        assert_eq!(big.to_string(), "18446744073709551616");
+        assert_eq!(sum([Answer::from(u64::MAX), Answer::from(1u32)], "summing"), Ok(big.clone()));
+        assert_eq!(parse_digits("18446744073709551616", "parsing"), Ok(big));
+    }
+}
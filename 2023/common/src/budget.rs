@@ -0,0 +1,286 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/*
+ * Time and memory budgets for a solver run, with cooperative cancellation.
+ *
+ * The runner installs a budget on the thread it solves on, and long loops in the solvers call
+ * checkpoint(), which fails with Cancelled once the budget is exhausted (or the run is cancelled
+ * from another thread), so that the solver can stop cleanly and report how far it got. Without a
+ * budget installed checkpoint() always succeeds.
+ *
+ * Memory is only measured when the binary counts its allocations with CountingAllocator:
+ *
+ * #[global_allocator]
+ * static ALLOCATOR: CountingAllocator = CountingAllocator;
+ *
+ * A budget counts the memory allocated on its own thread, so that runs on other threads, with
+ * budgets of their own or none, do not count towards it. Memory freed on another thread than the
+ * one it was allocated on still counts towards the thread it was allocated on.
+ */
+
+thread_local! {
+    // Bytes allocated on this thread less those freed on it, which may be below 0
+    static THREAD_ALLOCATED: Cell<isize> = const { Cell::new(0) };
+}
+
+fn count_on_thread(change: isize) {
+    // Allocations made while the thread is being torn down are not counted
+    let _ = THREAD_ALLOCATED.try_with(|allocated| allocated.set(allocated.get().wrapping_add(change)));
+}
+
+/*
+ * Allocator that counts the bytes allocated through it, on top of the system allocator.
+ */
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            count_on_thread(layout.size() as isize);
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        count_on_thread(-(layout.size() as isize));
+    }
+}
+
+// Bytes allocated on this thread less those freed on it, if allocations are counted with
+// CountingAllocator (otherwise 0)
+fn allocated_on_thread() -> isize {
+    THREAD_ALLOCATED.with(Cell::get)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Budget {
+    pub timeout: Option<Duration>,
+    pub max_memory: Option<usize>,
+}
+
+impl Budget {
+    /*
+     * Installs the budget on this thread until the guard returned is dropped. The time starts
+     * counting now, and memory counts from what this thread has allocated now.
+     */
+    pub fn install(&self) -> BudgetGuard {
+        let state = Arc::new(BudgetState {
+            deadline: self.timeout.map(|timeout| Instant::now() + timeout),
+            timeout: self.timeout,
+            memory_limit: self.max_memory.map(|max_memory| {
+                (allocated_on_thread().saturating_add(max_memory.try_into().unwrap_or(isize::MAX)), max_memory)
+            }),
+            cancelled: AtomicBool::new(false),
+        });
+        CURRENT.with(|current| *current.borrow_mut() = Some(Arc::clone(&state)));
+        BudgetGuard { state }
+    }
+}
+
+struct BudgetState {
+    deadline: Option<Instant>,
+    timeout: Option<Duration>,
+    // Allocated on the thread at which to cancel, and the budget it allows
+    memory_limit: Option<(isize, usize)>,
+    cancelled: AtomicBool,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<BudgetState>>> = const { RefCell::new(None) };
+}
+
+/*
+ * Keeps a budget installed. Can be used to cancel the run from another thread.
+ */
+pub struct BudgetGuard {
+    state: Arc<BudgetState>,
+}
+
+impl BudgetGuard {
+    pub fn canceller(&self) -> Canceller {
+        Canceller(Arc::clone(&self.state))
+    }
+}
+
+impl Drop for BudgetGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = None);
+    }
+}
+
+#[derive(Clone)]
+pub struct Canceller(Arc<BudgetState>);
+
+impl Canceller {
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/*
+ * Why a run was stopped.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cancelled(pub String);
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cancelled: {}", self.0)
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/*
+ * Fails if the budget installed on this thread is exhausted, or the run was cancelled.
+ */
+pub fn checkpoint() -> Result<(), Cancelled> {
+    CURRENT.with(|current| {
+        let current = current.borrow();
+        let Some(state) = current.as_ref() else {
+            return Ok(());
+        };
+        if state.cancelled.load(Ordering::Relaxed) {
+            return Err(Cancelled("run was cancelled".to_string()));
+        }
+        if let (Some(deadline), Some(timeout)) = (state.deadline, state.timeout) {
+            if Instant::now() >= deadline {
+                return Err(Cancelled(format!("time limit of {} exceeded", format_duration(timeout))));
+            }
+        }
+        if let Some((limit, max_memory)) = state.memory_limit {
+            if allocated_on_thread() > limit {
+                return Err(Cancelled(format!("memory limit of {} exceeded", format_size(max_memory))));
+            }
+        }
+        Ok(())
+    })
+}
+
+/*
+ * Parses a duration such as 10s, 500ms, 2m or 1h (seconds if no unit is given).
+ */
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let seconds = match unit {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/*
+ * Parses a size such as 512M, 2G, 64K or 1000 (bytes), in powers of 1024.
+ */
+pub fn parse_size(text: &str) -> Option<usize> {
+    let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: usize = number.parse().ok()?;
+    let multiplier: usize = match unit.to_ascii_uppercase().trim_end_matches('B') {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        _ => return None,
+    };
+    number.checked_mul(multiplier)
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{}s", duration.as_secs_f64())
+}
+
+fn format_size(size: usize) -> String {
+    match size {
+        size if size >= 1 << 30 && size % (1 << 30) == 0 => format!("{}G", size >> 30),
+        size if size >= 1 << 20 && size % (1 << 20) == 0 => format!("{}M", size >> 20),
+        size if size >= 1 << 10 && size % (1 << 10) == 0 => format!("{}K", size >> 10),
+        size => format!("{} bytes", size),
+    }
+}
+
+#[cfg(test)]
+mod budget_tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_parse_limits() {
+        assert_eq!(parse_duration("10s"), Some(Duration::from_secs(10)));
+        assert_eq!(parse_duration("500ms"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_duration("2m"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1.5"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("10 s"), None);
+        assert_eq!(parse_size("512M"), Some(512 << 20));
+        assert_eq!(parse_size("2gb"), Some(2 << 30));
+        assert_eq!(parse_size("1000"), Some(1000));
+        assert_eq!(parse_size("M"), None);
+    }
+
+    #[test]
+    fn test_checkpoint_without_budget() {
+        assert_eq!(checkpoint(), Ok(()));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let guard = Budget { timeout: Some(Duration::from_millis(20)), max_memory: None }.install();
+        assert_eq!(checkpoint(), Ok(()));
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(checkpoint(), Err(Cancelled("time limit of 0.02s exceeded".to_string())));
+        drop(guard);
+        assert_eq!(checkpoint(), Ok(()));
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let _guard = Budget { timeout: None, max_memory: Some(1 << 20) }.install();
+        let small = vec![0u8; 1 << 10];
+        assert_eq!(checkpoint(), Ok(()));
+        let large = vec![0u8; 3 << 19];
+        assert_eq!(checkpoint(), Err(Cancelled("memory limit of 1M exceeded".to_string())));
+        drop(large);
+        assert_eq!(checkpoint(), Ok(()));
+        drop(small);
+    }
+
+    #[test]
+    fn test_memory_counted_per_thread() {
+        let _guard = Budget { timeout: None, max_memory: Some(1 << 20) }.install();
+        // Another thread's allocations count only towards its own budget, which ends with it
+        let cancelled = std::thread::spawn(|| {
+            let _guard = Budget { timeout: None, max_memory: Some(1 << 10) }.install();
+            let large = vec![0u8; 3 << 19];
+            let cancelled = checkpoint();
+            drop(large);
+            cancelled
+        }).join().unwrap();
+        assert_eq!(cancelled, Err(Cancelled("memory limit of 1K exceeded".to_string())));
+        assert_eq!(checkpoint(), Ok(()));
+        let large = vec![0u8; 3 << 19];
+        assert_eq!(checkpoint(), Err(Cancelled("memory limit of 1M exceeded".to_string())));
+        drop(large);
+    }
+
+    #[test]
+    fn test_cancel_from_another_thread() {
+        let guard = Budget::default().install();
+        let canceller = guard.canceller();
+        std::thread::spawn(move || canceller.cancel()).join().unwrap();
+        assert_eq!(checkpoint().unwrap_err().to_string(), "cancelled: run was cancelled");
+    }
+}
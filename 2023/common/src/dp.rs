@@ -0,0 +1,99 @@
+use std::ops::{Index, IndexMut};
+
+/*
+ * Dense two-dimensional table for bottom-up dynamic programming.
+ *
+ * get and get_mut return None outside the table, which suits lookups of neighbouring cells at the
+ * edges. Indexing with table[(row, col)] panics with the offending index and table size instead.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DpTable<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> DpTable<T> {
+    pub fn new(rows: usize, cols: usize, initial: T) -> Self {
+        DpTable { rows, cols, cells: vec![initial; rows * cols] }
+    }
+}
+
+impl<T> DpTable<T> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn offset(&self, row: usize, col: usize) -> Option<usize> {
+        (row < self.rows && col < self.cols).then(|| row * self.cols + col)
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.offset(row, col).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.offset(row, col).map(|i| &mut self.cells[i])
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.rows).then(|| &self.cells[row * self.cols..(row + 1) * self.cols])
+    }
+
+    fn checked_offset(&self, (row, col): (usize, usize)) -> usize {
+        self.offset(row, col).unwrap_or_else(|| {
+            panic!("index ({}, {}) out of bounds for {}x{} table", row, col, self.rows, self.cols)
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for DpTable<T> {
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &T {
+        &self.cells[self.checked_offset(index)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for DpTable<T> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut T {
+        let offset = self.checked_offset(index);
+        &mut self.cells[offset]
+    }
+}
+
+#[cfg(test)]
+mod dp_tests {
+    use super::*;
+
+    #[test]
+    fn test_dp_table_bounds() {
+        // Count lattice paths through a 3x4 grid
+        let mut table = DpTable::new(3, 4, 0u64);
+        for row in 0..table.rows() {
+            for col in 0..table.cols() {
+                table[(row, col)] = if row == 0 || col == 0 {
+                    1
+                } else {
+                    table[(row - 1, col)] + table[(row, col - 1)]
+                };
+            }
+        }
+        assert_eq!(table[(2, 3)], 10);
+        assert_eq!(table.row(2), Some(&[1, 3, 6, 10][..]));
+        assert_eq!(table.get(3, 0), None);
+        assert_eq!(table.get(0, 4), None);
+        assert_eq!(table.row(3), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "index (0, 4) out of bounds for 3x4 table")]
+    fn test_dp_table_index_out_of_bounds() {
+        let table = DpTable::new(3, 4, 0);
+        let _ = table[(0, 4)];
+    }
+}
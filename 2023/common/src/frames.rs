@@ -0,0 +1,401 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/*
+ * Visualisation of a solver's progress as a sequence of frames.
+ *
+ * A frame is a grid of coloured characters plus a caption. Solvers emit frames into a FrameSink as
+ * they go; a Recording collects them, and can then be replayed in the terminal or written out as
+ * an animated GIF (feature gif) or a sequence of PNG images (feature png). Each character cell
+ * becomes a solid block of its colour in images, with spaces left as background.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colour {
+    Plain,
+    Dim,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Colour {
+    /*
+     * Colours to cycle through when distinguishing a number of things, e.g. segments of a range.
+     */
+    pub const CYCLE: [Colour; 5] = [Colour::Green, Colour::Blue, Colour::Yellow, Colour::Magenta, Colour::Cyan];
+
+    fn ansi(self) -> &'static str {
+        match self {
+            Colour::Plain => "\x1b[0m",
+            Colour::Dim => "\x1b[2m",
+            Colour::Red => "\x1b[31m",
+            Colour::Green => "\x1b[32m",
+            Colour::Yellow => "\x1b[33m",
+            Colour::Blue => "\x1b[34m",
+            Colour::Magenta => "\x1b[35m",
+            Colour::Cyan => "\x1b[36m",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<(char, Colour)>,
+    pub caption: String,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Frame { width, height, cells: vec![(' ', Colour::Plain); width * height], caption: String::new() }
+    }
+
+    /*
+     * Frame showing lines of text in plain colour, as wide as the longest line.
+     */
+    pub fn from_lines<S: AsRef<str>>(lines: &[S]) -> Self {
+        let width = lines.iter().map(|line| line.as_ref().chars().count()).max().unwrap_or(0);
+        let mut frame = Frame::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.as_ref().chars().enumerate() {
+                frame.set(x, y, c, Colour::Plain);
+            }
+        }
+        frame
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<(char, Colour)> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+
+    /*
+     * Sets a cell. Cells outside the frame are ignored, so callers can draw without clipping.
+     */
+    pub fn set(&mut self, x: usize, y: usize, c: char, colour: Colour) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = (c, colour);
+        }
+    }
+
+    /*
+     * Changes the colour of a cell, keeping its character.
+     */
+    pub fn paint(&mut self, x: usize, y: usize, colour: Colour) {
+        if let Some((c, _)) = self.get(x, y) {
+            self.set(x, y, c, colour);
+        }
+    }
+
+    /*
+     * Renders the frame as text with ANSI colour codes, caption last.
+     */
+    pub fn to_ansi(&self) -> String {
+        let mut text = String::new();
+        for row in self.cells.chunks(self.width.max(1)).take(self.height) {
+            let mut current = Colour::Plain;
+            for &(c, colour) in row {
+                if colour != current {
+                    text.push_str(colour.ansi());
+                    current = colour;
+                }
+                text.push(c);
+            }
+            text.push_str(Colour::Plain.ansi());
+            text.push('\n');
+        }
+        text.push_str(&self.caption);
+        text.push('\n');
+        text
+    }
+}
+
+pub trait FrameSink {
+    /*
+     * Whether emitted frames are used at all. Solvers can check this to avoid building frames
+     * nobody will see.
+     */
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    fn emit(&mut self, frame: Frame);
+}
+
+#[derive(Debug, Default)]
+pub struct Recording {
+    pub frames: Vec<Frame>,
+    enabled: bool,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Recording { frames: Vec::new(), enabled: true }
+    }
+
+    /*
+     * Recording that ignores everything emitted into it.
+     */
+    pub fn disabled() -> Self {
+        Recording { frames: Vec::new(), enabled: false }
+    }
+}
+
+impl FrameSink for Recording {
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn emit(&mut self, frame: Frame) {
+        if self.enabled {
+            self.frames.push(frame);
+        }
+    }
+}
+
+/*
+ * Replays frames in the terminal, clearing the screen before each one.
+ */
+pub fn play<W: Write>(frames: &[Frame], delay: Duration, out: &mut W) -> io::Result<()> {
+    for frame in frames {
+        write!(out, "\x1b[2J\x1b[H{}", frame.to_ansi())?;
+        out.flush()?;
+        thread::sleep(delay);
+    }
+    Ok(())
+}
+
+/*
+ * Conversion of frames to image pixels, for the image encoders.
+ */
+#[cfg(any(feature = "gif", feature = "png"))]
+mod raster {
+    use super::*;
+
+    const BACKGROUND: [u8; 3] = [0x0f, 0x0f, 0x23];
+
+    impl Colour {
+        const ALL: [Colour; 8] = [
+            Colour::Plain, Colour::Dim, Colour::Red, Colour::Green,
+            Colour::Yellow, Colour::Blue, Colour::Magenta, Colour::Cyan,
+        ];
+
+        fn rgb(self) -> [u8; 3] {
+            match self {
+                Colour::Plain => [0xcc, 0xcc, 0xcc],
+                Colour::Dim => [0x44, 0x44, 0x44],
+                Colour::Red => [0xdd, 0x33, 0x33],
+                Colour::Green => [0x33, 0xcc, 0x33],
+                Colour::Yellow => [0xee, 0xcc, 0x22],
+                Colour::Blue => [0x33, 0x66, 0xee],
+                Colour::Magenta => [0xcc, 0x33, 0xcc],
+                Colour::Cyan => [0x33, 0xcc, 0xcc],
+            }
+        }
+
+        // Index in an image palette, where 0 is the background
+        pub(crate) fn palette_index(self) -> u8 {
+            Colour::ALL.iter().position(|&c| c == self).unwrap() as u8 + 1
+        }
+    }
+
+    impl Frame {
+        // Each cell as a cell_size square block of palette indices
+        pub(crate) fn rasterise(&self, cell_size: usize) -> Vec<u8> {
+            let pixel_width = self.width * cell_size;
+            let mut pixels = vec![0; pixel_width * self.height * cell_size];
+            for (i, &(c, colour)) in self.cells.iter().enumerate() {
+                if c == ' ' {
+                    continue;
+                }
+                let (x, y) = (i % self.width * cell_size, i / self.width * cell_size);
+                for row in y..y + cell_size {
+                    pixels[row * pixel_width + x..row * pixel_width + x + cell_size].fill(colour.palette_index());
+                }
+            }
+            pixels
+        }
+    }
+
+    pub(super) fn palette() -> Vec<u8> {
+        std::iter::once(BACKGROUND).chain(Colour::ALL.iter().map(|c| c.rgb())).flatten().collect()
+    }
+
+    pub(super) fn frame_size(frames: &[Frame], cell_size: usize) -> io::Result<(u16, u16)> {
+        let first = frames.first().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no frames to write"))?;
+        let size = |cells: usize| u16::try_from(cells * cell_size)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame too large for an image"));
+        Ok((size(first.width)?, size(first.height)?))
+    }
+}
+
+/*
+ * Writes frames as an animated GIF that loops forever. All frames must be the size of the first.
+ */
+#[cfg(feature = "gif")]
+pub fn write_gif(frames: &[Frame], path: &Path, cell_size: usize, delay: Duration) -> io::Result<()> {
+    let (width, height) = raster::frame_size(frames, cell_size)?;
+    let file = std::fs::File::create(path)?;
+    let mut encoder = gif::Encoder::new(io::BufWriter::new(file), width, height, &raster::palette())
+        .map_err(io::Error::other)?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+    for frame in frames {
+        let mut gif_frame = gif::Frame::from_indexed_pixels(width, height, frame.rasterise(cell_size), None);
+        gif_frame.delay = (delay.as_millis() / 10) as u16;
+        encoder.write_frame(&gif_frame).map_err(io::Error::other)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "gif"))]
+pub fn write_gif(_frames: &[Frame], _path: &Path, _cell_size: usize, _delay: Duration) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "built without GIF support (build with --features gif)"))
+}
+
+/*
+ * Writes each frame as a numbered PNG image (frame_0000.png, ...) in the directory given, which
+ * is created if needed. Returns the paths written.
+ */
+#[cfg(feature = "png")]
+pub fn write_png_sequence(frames: &[Frame], directory: &Path, cell_size: usize) -> io::Result<Vec<PathBuf>> {
+    let (width, height) = raster::frame_size(frames, cell_size)?;
+    std::fs::create_dir_all(directory)?;
+    let palette = raster::palette();
+    frames.iter().enumerate().map(|(i, frame)| {
+        let path = directory.join(format!("frame_{:04}.png", i));
+        let file = std::fs::File::create(&path)?;
+        let mut encoder = png::Encoder::new(io::BufWriter::new(file), width as u32, height as u32);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(palette.clone());
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer.write_image_data(&frame.rasterise(cell_size)).map_err(io::Error::other)?;
+        Ok(path)
+    }).collect()
+}
+
+#[cfg(not(feature = "png"))]
+pub fn write_png_sequence(_frames: &[Frame], _directory: &Path, _cell_size: usize) -> io::Result<Vec<PathBuf>> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "built without PNG support (build with --features png)"))
+}
+
+/*
+ * Where a solver's frames should go, as chosen on its command line with one of:
+ *
+ * --animate        replay in the terminal once solved
+ * --gif FILE       write an animated GIF
+ * --png DIRECTORY  write a sequence of PNG images
+ *
+ * optionally with --delay MILLISECONDS between frames (default 100).
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameOutput {
+    None,
+    Terminal,
+    Gif(PathBuf),
+    Png(PathBuf),
+}
+
+pub struct FrameOptions {
+    pub output: FrameOutput,
+    pub delay: Duration,
+}
+
+const CELL_SIZE: usize = 4;
+
+impl FrameOptions {
+    pub fn from_args(args: &[String]) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+        let mut options = FrameOptions { output: FrameOutput::None, delay: Duration::from_millis(100) };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| invalid(format!("{} needs a value", arg)));
+            match arg.as_str() {
+                "--animate" => options.output = FrameOutput::Terminal,
+                "--gif" => options.output = FrameOutput::Gif(PathBuf::from(value()?)),
+                "--png" => options.output = FrameOutput::Png(PathBuf::from(value()?)),
+                "--delay" => {
+                    let millis = value()?.parse().map_err(|_| invalid("--delay needs milliseconds".to_string()))?;
+                    options.delay = Duration::from_millis(millis);
+                }
+                _ => return Err(invalid(format!("unknown option {}", arg))),
+            }
+        }
+        Ok(options)
+    }
+
+    pub fn recording(&self) -> Recording {
+        match self.output {
+            FrameOutput::None => Recording::disabled(),
+            _ => Recording::new(),
+        }
+    }
+
+    pub fn write(&self, recording: &Recording) -> io::Result<()> {
+        match &self.output {
+            FrameOutput::None => Ok(()),
+            FrameOutput::Terminal => play(&recording.frames, self.delay, &mut io::stdout()),
+            FrameOutput::Gif(path) => write_gif(&recording.frames, path, CELL_SIZE, self.delay),
+            FrameOutput::Png(directory) => write_png_sequence(&recording.frames, directory, CELL_SIZE).map(|_| ()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod frames_tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_drawing() {
+        let mut frame = Frame::from_lines(&["467..", ".*"]).with_caption("row 0");
+        assert_eq!((frame.width(), frame.height()), (5, 2));
+        assert_eq!(frame.get(1, 1), Some(('*', Colour::Plain)));
+        assert_eq!(frame.get(4, 1), Some((' ', Colour::Plain)));
+        frame.paint(1, 1, Colour::Yellow);
+        frame.set(9, 9, '#', Colour::Red);
+        assert_eq!(frame.get(1, 1), Some(('*', Colour::Yellow)));
+        assert_eq!(frame.get(9, 9), None);
+        assert_eq!(frame.to_ansi(), "467..\x1b[0m\n.\x1b[33m*\x1b[0m   \x1b[0m\nrow 0\n");
+    }
+
+    #[cfg(any(feature = "gif", feature = "png"))]
+    #[test]
+    fn test_rasterise() {
+        let mut frame = Frame::new(2, 1);
+        frame.set(1, 0, '#', Colour::Red);
+        let red = Colour::Red.palette_index();
+        assert_eq!(frame.rasterise(2), vec![0, 0, red, red, 0, 0, red, red]);
+        assert_eq!(raster::palette().len(), 3 * 9);
+    }
+
+    #[test]
+    fn test_frame_options() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+        let options = FrameOptions::from_args(&args("--gif scan.gif --delay 50")).unwrap();
+        assert_eq!(options.output, FrameOutput::Gif(PathBuf::from("scan.gif")));
+        assert_eq!(options.delay, Duration::from_millis(50));
+        assert!(options.recording().enabled());
+        assert!(!FrameOptions::from_args(&[]).unwrap().recording().enabled());
+        assert!(FrameOptions::from_args(&args("--png")).is_err());
+        assert!(FrameOptions::from_args(&args("--bogus")).is_err());
+    }
+}
@@ -0,0 +1,341 @@
+use std::fmt;
+
+/*
+ * Declarative grammars for puzzle inputs, to validate an input before solving it.
+ *
+ * A grammar is a sequence of rules over the lines of the input, and each line is matched against
+ * a sequence of tokens. For example, a day 6 input:
+ *
+ * Time:      7  15   30
+ * Distance:  9  40  200
+ *
+ * is described by:
+ *
+ * Grammar(&[
+ *     Rule::Line(&[Token::Literal("Time:"), Token::Spaces, Token::Number, Token::Many(&[Token::Spaces, Token::Number])]),
+ *     Rule::Line(&[Token::Literal("Distance:"), Token::Spaces, Token::Number, Token::Many(&[Token::Spaces, Token::Number])]),
+ * ])
+ *
+ * Validation carries on past a line that does not match, so that every bad line is reported, each
+ * with the furthest point it could be matched to and what was expected there.
+ */
+
+#[derive(Debug, Clone, Copy)]
+pub enum Token {
+    // Exactly this text
+    Literal(&'static str),
+    // One or more ASCII digits
+    Number,
+    // One or more lowercase ASCII letters
+    Word,
+    // One or more spaces
+    Spaces,
+    // One of these texts
+    OneOf(&'static [&'static str]),
+    // A single character for which the function holds, described by the text
+    Char(fn(char) -> bool, &'static str),
+    // The tokens, zero or more times over
+    Many(&'static [Token]),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Rule {
+    // One line of the tokens
+    Line(&'static [Token]),
+    // One or more lines of the tokens, up to a blank line or the end of the input
+    Lines(&'static [Token]),
+    // One blank line
+    Blank,
+    // One or more lines of the same width, of characters for which the function holds
+    Grid(fn(char) -> bool, &'static str),
+    // The rules, one or more times over, each time separated by a blank line
+    Sections(&'static [Rule]),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Grammar(pub &'static [Rule]);
+
+/*
+ * A place where the input does not follow its grammar. Line and column count from 1, and the
+ * column counts characters.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub line: usize,
+    pub column: usize,
+    pub expected: Vec<String>,
+    pub found: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let expected = match self.expected.as_slice() {
+            [only] => only.clone(),
+            [rest @ .., last] => format!("{} or {}", rest.join(", "), last),
+            [] => "nothing".to_string(),
+        };
+        write!(f, "line {}, column {}: expected {}, found {}", self.line, self.column, expected, self.found)
+    }
+}
+
+impl Grammar {
+    /*
+     * Returns every violation of the grammar by the input, in order. An input without any
+     * is valid.
+     */
+    pub fn validate(&self, input: &str) -> Vec<Violation> {
+        let lines: Vec<&str> = input.lines().collect();
+        let mut validator = Validator { lines: &lines, index: 0, violations: Vec::new() };
+        validator.rules(self.0);
+
+        while validator.index < lines.len() && lines[validator.index].is_empty() {
+            validator.index += 1;
+        }
+        if validator.index < lines.len() {
+            let line = lines[validator.index];
+            validator.violation(0, vec!["end of input".to_string()], describe_found(line, 0));
+        }
+        validator.violations
+    }
+}
+
+fn describe(token: &Token) -> Vec<String> {
+    match token {
+        Token::Literal(text) => vec![format!("'{}'", text)],
+        Token::Number => vec!["number".to_string()],
+        Token::Word => vec!["word".to_string()],
+        Token::Spaces => vec!["space".to_string()],
+        Token::OneOf(texts) => texts.iter().map(|text| format!("'{}'", text)).collect(),
+        Token::Char(_, description) => vec![description.to_string()],
+        Token::Many(tokens) => tokens.first().map(describe).unwrap_or_default(),
+    }
+}
+
+fn describe_found(line: &str, column: usize) -> String {
+    match line.chars().nth(column) {
+        Some(c) => format!("'{}'", c),
+        None => "end of line".to_string(),
+    }
+}
+
+struct Validator<'a> {
+    lines: &'a [&'a str],
+    index: usize,
+    violations: Vec<Violation>,
+}
+
+impl Validator<'_> {
+    fn violation(&mut self, column: usize, expected: Vec<String>, found: String) {
+        self.violations.push(Violation { line: self.index + 1, column: column + 1, expected, found });
+    }
+
+    fn current(&self) -> Option<&str> {
+        self.lines.get(self.index).copied()
+    }
+
+    fn rules(&mut self, rules: &[Rule]) {
+        for rule in rules {
+            self.rule(rule);
+        }
+    }
+
+    fn rule(&mut self, rule: &Rule) {
+        match rule {
+            Rule::Line(tokens) => self.line(tokens),
+            Rule::Lines(tokens) => {
+                self.line(tokens);
+                while self.current().is_some_and(|line| !line.is_empty()) {
+                    self.line(tokens);
+                }
+            }
+            Rule::Blank => match self.current() {
+                Some("") => self.index += 1,
+                Some(line) => self.violation(0, vec!["blank line".to_string()], describe_found(line, 0)),
+                None => self.violation(0, vec!["blank line".to_string()], "end of input".to_string()),
+            },
+            Rule::Grid(allowed, description) => self.grid(*allowed, description),
+            Rule::Sections(rules) => loop {
+                self.rules(rules);
+                let more = self.current() == Some("")
+                    && self.lines.get(self.index + 1).is_some_and(|line| !line.is_empty());
+                if !more {
+                    break;
+                }
+                self.index += 1;
+            },
+        }
+    }
+
+    /*
+     * Matches the current line against the tokens and moves on to the next, whether or not it
+     * matched.
+     */
+    fn line(&mut self, tokens: &[Token]) {
+        let Some(line) = self.current() else {
+            let expected = tokens.first().map(describe).unwrap_or_default();
+            self.violation(0, expected, "end of input".to_string());
+            return;
+        };
+        let mut matcher = LineMatcher { chars: line.chars().collect(), farthest: 0, expected: Vec::new() };
+        match matcher.tokens(tokens, 0) {
+            Some(end) if end == matcher.chars.len() => {}
+            Some(end) => {
+                matcher.fail(end, vec!["end of line".to_string()]);
+                self.violation(matcher.farthest, matcher.expected, describe_found(line, matcher.farthest));
+            }
+            None => self.violation(matcher.farthest, matcher.expected, describe_found(line, matcher.farthest)),
+        }
+        self.index += 1;
+    }
+
+    fn grid(&mut self, allowed: fn(char) -> bool, description: &str) {
+        let Some(width) = self.current().map(|line| line.chars().count()).filter(|&width| width > 0) else {
+            let found = self.current().map(|line| describe_found(line, 0)).unwrap_or("end of input".to_string());
+            self.violation(0, vec![description.to_string()], found);
+            return;
+        };
+        while let Some(line) = self.current().filter(|line| !line.is_empty()) {
+            let bad = line.chars().take(width).position(|c| !allowed(c));
+            let length = line.chars().count();
+            if let Some(column) = bad {
+                self.violation(column, vec![description.to_string()], describe_found(line, column));
+            } else if length > width {
+                let expected = format!("end of line (rows are {} wide)", width);
+                self.violation(width, vec![expected], describe_found(line, width));
+            } else if length < width {
+                let expected = format!("{} (rows are {} wide)", description, width);
+                self.violation(length, vec![expected], "end of line".to_string());
+            }
+            self.index += 1;
+        }
+    }
+}
+
+/*
+ * Matches tokens within one line, keeping track of the furthest column at which a token failed to
+ * match, and what would have matched there.
+ */
+struct LineMatcher {
+    chars: Vec<char>,
+    farthest: usize,
+    expected: Vec<String>,
+}
+
+impl LineMatcher {
+    fn fail(&mut self, position: usize, expected: Vec<String>) -> Option<usize> {
+        if position > self.farthest {
+            self.farthest = position;
+            self.expected.clear();
+        }
+        if position == self.farthest {
+            for item in expected {
+                if !self.expected.contains(&item) {
+                    self.expected.push(item);
+                }
+            }
+        }
+        None
+    }
+
+    fn run_of(&self, position: usize, belongs: impl Fn(char) -> bool) -> usize {
+        self.chars[position..].iter().take_while(|&&c| belongs(c)).count()
+    }
+
+    fn starts_with(&self, position: usize, text: &str) -> bool {
+        let mut chars = self.chars[position..].iter();
+        text.chars().all(|c| chars.next() == Some(&c))
+    }
+
+    fn tokens(&mut self, tokens: &[Token], mut position: usize) -> Option<usize> {
+        for token in tokens {
+            position = self.token(token, position)?;
+        }
+        Some(position)
+    }
+
+    fn token(&mut self, token: &Token, position: usize) -> Option<usize> {
+        let run = match token {
+            Token::Literal(text) => self.starts_with(position, text).then(|| text.chars().count()),
+            Token::Number => Some(self.run_of(position, |c| c.is_ascii_digit())).filter(|&n| n > 0),
+            Token::Word => Some(self.run_of(position, |c| c.is_ascii_lowercase())).filter(|&n| n > 0),
+            Token::Spaces => Some(self.run_of(position, |c| c == ' ')).filter(|&n| n > 0),
+            Token::OneOf(texts) => texts.iter()
+                .find(|text| self.starts_with(position, text))
+                .map(|text| text.chars().count()),
+            Token::Char(allowed, _) => self.chars.get(position).filter(|&&c| allowed(c)).map(|_| 1),
+            Token::Many(tokens) => {
+                let mut end = position;
+                while let Some(next) = self.tokens(tokens, end).filter(|&next| next > end) {
+                    end = next;
+                }
+                return Some(end);
+            }
+        };
+        match run {
+            Some(length) => Some(position + length),
+            None => self.fail(position, describe(token)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod grammar_tests {
+    use super::*;
+
+    const RACES: Grammar = Grammar(&[
+        Rule::Line(&[Token::Literal("Time:"), Token::Spaces, Token::Number, Token::Many(&[Token::Spaces, Token::Number])]),
+        Rule::Line(&[Token::Literal("Distance:"), Token::Spaces, Token::Number, Token::Many(&[Token::Spaces, Token::Number])]),
+    ]);
+
+    #[test]
+    fn test_valid_input() {
+        assert_eq!(RACES.validate("Time:      7  15   30\nDistance:  9  40  200\n"), vec![]);
+    }
+
+    #[test]
+    fn test_every_violation_is_reported() {
+        let violations = RACES.validate("Time:      7  1x   30\nDistance  9  40  200\nSpeed: 1\n");
+        let messages: Vec<String> = violations.iter().map(Violation::to_string).collect();
+        assert_eq!(messages, vec![
+            "line 1, column 16: expected space or end of line, found 'x'",
+            "line 2, column 1: expected 'Distance:', found 'D'",
+            "line 3, column 1: expected end of input, found 'S'",
+        ]);
+    }
+
+    #[test]
+    fn test_missing_lines() {
+        let violations = RACES.validate("Time: 7\n");
+        assert_eq!(violations, vec![Violation {
+            line: 2,
+            column: 1,
+            expected: vec!["'Distance:'".to_string()],
+            found: "end of input".to_string(),
+        }]);
+    }
+
+    #[test]
+    fn test_sections_and_grids() {
+        const MAPS: Grammar = Grammar(&[
+            Rule::Line(&[Token::Literal("seeds:"), Token::Spaces, Token::Number]),
+            Rule::Blank,
+            Rule::Sections(&[
+                Rule::Line(&[Token::Word, Token::Literal(" map:")]),
+                Rule::Grid(|c| c == '.' || c == '#', "'.' or '#'"),
+            ]),
+        ]);
+        assert_eq!(MAPS.validate("seeds: 1\n\nsoil map:\n..#\n#..\n\nwater map:\n#\n"), vec![]);
+
+        let messages: Vec<String> = MAPS.validate("seeds: 1\nsoil map:\n..#\n#.\n..#.\n\nWater map:\n.x\n")
+            .iter()
+            .map(Violation::to_string)
+            .collect();
+        assert_eq!(messages, vec![
+            "line 2, column 1: expected blank line, found 's'",
+            "line 4, column 3: expected '.' or '#' (rows are 3 wide), found end of line",
+            "line 5, column 4: expected end of line (rows are 3 wide), found '.'",
+            "line 7, column 1: expected word, found 'W'",
+            "line 8, column 2: expected '.' or '#', found 'x'",
+        ]);
+    }
+}
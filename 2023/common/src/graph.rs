@@ -0,0 +1,370 @@
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque};
+use std::fmt;
+
+/*
+ * Directed graph with edge data.
+ *
+ * Nodes are any ordered, cloneable key, typically a String (e.g. almanac categories) or an integer.
+ * Nodes and edges are kept in insertion order per node inside a BTreeMap so that traversals are
+ * deterministic from one run to the next.
+ */
+#[derive(Debug, Clone)]
+pub struct Graph<N, E = ()> {
+    adjacency: BTreeMap<N, Vec<(N, E)>>,
+}
+
+/*
+ * Returned when an operation requiring an acyclic graph meets a cycle. Holds the nodes of the
+ * cycle in order, with the first node repeated at the end.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+impl<N: fmt::Debug> fmt::Display for Cycle<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "graph contains a cycle: {:?}", self.0)
+    }
+}
+
+impl<N: fmt::Debug> std::error::Error for Cycle<N> {}
+
+impl<N: Ord + Clone, E> Default for Graph<N, E> {
+    fn default() -> Self {
+        Graph::new()
+    }
+}
+
+impl<N: Ord + Clone, E> Graph<N, E> {
+    pub fn new() -> Self {
+        Graph { adjacency: BTreeMap::new() }
+    }
+
+    pub fn add_node(&mut self, node: N) {
+        self.adjacency.entry(node).or_default();
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, edge: E) {
+        self.add_node(to.clone());
+        self.adjacency.entry(from).or_default().push((to, edge));
+    }
+
+    pub fn contains_node<Q>(&self, node: &Q) -> bool
+    where
+        N: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.adjacency.contains_key(node)
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.adjacency.keys()
+    }
+
+    pub fn edges_from<Q>(&self, node: &Q) -> &[(N, E)]
+    where
+        N: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.adjacency.get(node).map_or(&[], |edges| edges.as_slice())
+    }
+
+    pub fn edge<Q>(&self, from: &Q, to: &Q) -> Option<&E>
+    where
+        N: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.edges_from(from).iter().find(|(n, _)| n.borrow() == to).map(|(_, e)| e)
+    }
+
+    /*
+     * Returns the edge data along a path of nodes, such as one returned by bfs_path, or None if
+     * any consecutive pair of nodes is not joined by an edge.
+     */
+    pub fn path_edges(&self, path: &[N]) -> Option<Vec<&E>> {
+        path.windows(2).map(|pair| self.edge(&pair[0], &pair[1])).collect()
+    }
+
+    fn key<Q>(&self, node: &Q) -> Option<&N>
+    where
+        N: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.adjacency.get_key_value(node).map(|(k, _)| k)
+    }
+
+    /*
+     * Returns all nodes reachable from start in breadth-first order, start included.
+     */
+    pub fn bfs<Q>(&self, start: &Q) -> Vec<N>
+    where
+        N: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let Some(start) = self.key(start) else { return Vec::new() };
+        let mut seen = BTreeSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        let mut order = Vec::new();
+        while let Some(node) = queue.pop_front() {
+            order.push(node.clone());
+            for (next, _) in self.edges_from::<N>(node) {
+                if seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        order
+    }
+
+    /*
+     * Returns a path from start to goal with the fewest edges, including both ends.
+     */
+    pub fn bfs_path<Q>(&self, start: &Q, goal: &Q) -> Option<Vec<N>>
+    where
+        N: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let start = self.key(start)?;
+        let goal = self.key(goal)?;
+        let mut previous: BTreeMap<&N, &N> = BTreeMap::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            if node == goal {
+                return Some(unwind_path(&previous, start, goal));
+            }
+            for (next, _) in self.edges_from::<N>(node) {
+                if next != start && !previous.contains_key(next) {
+                    previous.insert(next, node);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /*
+     * Orders all nodes so that every edge goes from an earlier node to a later one (Kahn's
+     * algorithm). Ties are broken by node order, so the result is stable. Fails with one of the
+     * cycles if the graph is not acyclic.
+     */
+    pub fn topological_sort(&self) -> Result<Vec<N>, Cycle<N>> {
+        let mut in_degree: BTreeMap<&N, usize> = self.adjacency.keys().map(|n| (n, 0)).collect();
+        for (next, _) in self.adjacency.values().flatten() {
+            *in_degree.entry(next).or_default() += 1;
+        }
+        let mut ready: BTreeSet<&N> = in_degree.iter().filter(|&(_, &d)| d == 0).map(|(&n, _)| n).collect();
+        let mut order = Vec::new();
+        while let Some(node) = ready.pop_first() {
+            order.push(node.clone());
+            for (next, _) in self.edges_from(node) {
+                let degree = in_degree.get_mut(next).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.insert(next);
+                }
+            }
+        }
+        if order.len() == self.adjacency.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle().expect("unsorted nodes imply a cycle"))
+        }
+    }
+
+    /*
+     * Finds a cycle by depth-first search, if there is one.
+     */
+    pub fn find_cycle(&self) -> Option<Cycle<N>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State { OnStack, Done }
+
+        let mut state: BTreeMap<&N, State> = BTreeMap::new();
+        for root in self.adjacency.keys() {
+            if state.contains_key(root) {
+                continue;
+            }
+            // Iterative DFS: each stack entry is a node and the index of its next edge to explore
+            let mut stack: Vec<(&N, usize)> = vec![(root, 0)];
+            state.insert(root, State::OnStack);
+            while let Some((node, index)) = stack.last_mut() {
+                match self.edges_from(*node).get(*index) {
+                    Some((next, _)) => {
+                        *index += 1;
+                        match state.get(next) {
+                            Some(State::OnStack) => {
+                                let from = stack.iter().position(|(n, _)| *n == next).unwrap();
+                                let mut cycle: Vec<N> = stack[from..].iter().map(|(n, _)| (*n).clone()).collect();
+                                cycle.push(next.clone());
+                                return Some(Cycle(cycle));
+                            }
+                            Some(State::Done) => {}
+                            None => {
+                                state.insert(next, State::OnStack);
+                                stack.push((next, 0));
+                            }
+                        }
+                    }
+                    None => {
+                        state.insert(node, State::Done);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /*
+     * Dijkstra's algorithm. Returns the lowest total weight to every node reachable from start,
+     * where weight gives the (non-negative) cost of each edge.
+     */
+    pub fn dijkstra<Q, F>(&self, start: &Q, weight: F) -> BTreeMap<N, u64>
+    where
+        N: Borrow<Q>,
+        Q: Ord + ?Sized,
+        F: Fn(&E) -> u64,
+    {
+        let Some(start) = self.key(start) else { return BTreeMap::new() };
+        self.dijkstra_with_previous(start, None, &weight).0
+            .into_iter()
+            .map(|(n, d)| (n.clone(), d))
+            .collect()
+    }
+
+    fn dijkstra_with_previous<'a, F>(&'a self, start: &'a N, goal: Option<&N>, weight: &F) -> (BTreeMap<&'a N, u64>, BTreeMap<&'a N, &'a N>)
+    where
+        F: Fn(&E) -> u64,
+    {
+        let mut distance: BTreeMap<&N, u64> = BTreeMap::from([(start, 0)]);
+        let mut previous: BTreeMap<&N, &N> = BTreeMap::new();
+        let mut heap = BinaryHeap::from([Reverse((0, start))]);
+        while let Some(Reverse((cost, node))) = heap.pop() {
+            if distance.get(node).is_some_and(|&best| cost > best) {
+                continue;
+            }
+            if Some(node) == goal {
+                break;
+            }
+            for (next, edge) in self.edges_from(node) {
+                let next_cost = cost + weight(edge);
+                if distance.get(next).is_none_or(|&best| next_cost < best) {
+                    distance.insert(next, next_cost);
+                    previous.insert(next, node);
+                    heap.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+        (distance, previous)
+    }
+
+    /*
+     * Returns the lowest total weight from start to goal and the path achieving it.
+     */
+    pub fn shortest_path<Q, F>(&self, start: &Q, goal: &Q, weight: F) -> Option<(u64, Vec<N>)>
+    where
+        N: Borrow<Q>,
+        Q: Ord + ?Sized,
+        F: Fn(&E) -> u64,
+    {
+        let start = self.key(start)?;
+        let goal = self.key(goal)?;
+        let (distance, previous) = self.dijkstra_with_previous(start, Some(goal), &weight);
+        let total = *distance.get(goal)?;
+        Some((total, unwind_path(&previous, start, goal)))
+    }
+
+    /*
+     * Returns the highest total weight from start to goal and the path achieving it.
+     *
+     * Longest paths are only well defined without cycles, so this fails if the graph has one.
+     */
+    pub fn longest_path<Q, F>(&self, start: &Q, goal: &Q, weight: F) -> Result<Option<(u64, Vec<N>)>, Cycle<N>>
+    where
+        N: Borrow<Q>,
+        Q: Ord + ?Sized,
+        F: Fn(&E) -> u64,
+    {
+        let order = self.topological_sort()?;
+        let (Some(start), Some(goal)) = (self.key(start), self.key(goal)) else { return Ok(None) };
+        let mut distance: BTreeMap<&N, u64> = BTreeMap::from([(start, 0)]);
+        let mut previous: BTreeMap<&N, &N> = BTreeMap::new();
+        for node in order.iter().skip_while(|&n| n != start) {
+            let node = self.key::<N>(node).unwrap();
+            let Some(&cost) = distance.get(node) else { continue };
+            for (next, edge) in self.edges_from::<N>(node) {
+                let next_cost = cost + weight(edge);
+                if distance.get(next).is_none_or(|&best| next_cost > best) {
+                    distance.insert(next, next_cost);
+                    previous.insert(next, node);
+                }
+            }
+        }
+        Ok(distance.get(goal).map(|&total| (total, unwind_path(&previous, start, goal))))
+    }
+}
+
+fn unwind_path<N: Ord + Clone>(previous: &BTreeMap<&N, &N>, start: &N, goal: &N) -> Vec<N> {
+    let mut path = vec![goal.clone()];
+    let mut current = goal;
+    while current != start {
+        current = previous[current];
+        path.push(current.clone());
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod graph_tests {
+    use super::*;
+
+    fn almanac_chain() -> Graph<String> {
+        let mut graph = Graph::new();
+        for (from, to) in [("seed", "soil"), ("soil", "fertilizer"), ("fertilizer", "water"), ("water", "location")] {
+            graph.add_edge(from.to_string(), to.to_string(), ());
+        }
+        graph
+    }
+
+    fn weighted_dag() -> Graph<i32, u64> {
+        //   1 -(1)-> 2 -(1)-> 4
+        //   1 -(5)-> 3 -(1)-> 4
+        let mut graph = Graph::new();
+        graph.add_edge(1, 2, 1);
+        graph.add_edge(1, 3, 5);
+        graph.add_edge(2, 4, 1);
+        graph.add_edge(3, 4, 1);
+        graph
+    }
+
+    #[test]
+    fn test_bfs_path_over_string_keys() {
+        let graph = almanac_chain();
+        assert_eq!(graph.bfs_path("seed", "location").unwrap(), vec!["seed", "soil", "fertilizer", "water", "location"]);
+        assert_eq!(graph.bfs_path("soil", "soil").unwrap(), vec!["soil"]);
+        assert_eq!(graph.bfs_path("location", "seed"), None);
+        assert_eq!(graph.bfs("water"), vec!["water", "location"]);
+    }
+
+    #[test]
+    fn test_topological_sort_and_cycles() {
+        let mut graph = weighted_dag();
+        assert_eq!(graph.topological_sort(), Ok(vec![1, 2, 3, 4]));
+        assert_eq!(graph.find_cycle(), None);
+
+        graph.add_edge(4, 1, 1);
+        assert_eq!(graph.find_cycle(), Some(Cycle(vec![1, 2, 4, 1])));
+        assert!(graph.topological_sort().is_err());
+        assert!(graph.longest_path(&1, &4, |&w| w).is_err());
+    }
+
+    #[test]
+    fn test_shortest_and_longest_paths() {
+        let graph = weighted_dag();
+        assert_eq!(graph.shortest_path(&1, &4, |&w| w), Some((2, vec![1, 2, 4])));
+        assert_eq!(graph.longest_path(&1, &4, |&w| w), Ok(Some((6, vec![1, 3, 4]))));
+        assert_eq!(graph.dijkstra(&1, |&w| w), BTreeMap::from([(1, 0), (2, 1), (3, 5), (4, 2)]));
+        assert_eq!(graph.shortest_path(&4, &1, |&w| w), None);
+    }
+}
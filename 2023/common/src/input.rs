@@ -0,0 +1,85 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+/*
+ * Inputs too large to read comfortably into memory.
+ *
+ * The file is memory-mapped, and its lines are handed to solvers as slices borrowed from the
+ * mapping, so nothing is copied into a String per line (or at all) before the solver sees it:
+ *
+ * let input = MappedInput::open(path)?;
+ * let solution = day4::sum_cards_lines(input.lines())?;
+ */
+
+pub struct MappedInput {
+    // Empty files are not mapped, as mapping zero bytes fails on some platforms
+    map: Option<Mmap>,
+}
+
+impl MappedInput {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(MappedInput { map: None });
+        }
+        // Safety: the input must not be truncated or written to while it is mapped, which the
+        // solvers have never had to guard against for the files they read either
+        let map = unsafe { Mmap::map(&file)? };
+        #[cfg(unix)]
+        map.advise(memmap2::Advice::Sequential)?;
+        Ok(MappedInput { map: Some(map) })
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or_default()
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &[u8]> {
+        lines(self.bytes())
+    }
+}
+
+/*
+ * Splits bytes into lines the way BufRead::lines does: at each '\n', dropping a '\r' before it,
+ * and without an empty line after a final '\n'.
+ */
+pub fn lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    bytes.split_inclusive(|&byte| byte == b'\n').map(|line| {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        line.strip_suffix(b"\r").unwrap_or(line)
+    })
+}
+
+#[cfg(test)]
+mod input_tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::io::BufRead;
+
+    #[test]
+    fn test_lines_match_buf_read() {
+        for text in ["", "\n", "a", "a\nb", "a\nb\n", "a\r\nb\r\n", "a\n\nb\n\n", "a\rb\n"] {
+            let expected: Vec<Vec<u8>> = text.as_bytes().lines().map(|line| line.unwrap().into_bytes()).collect();
+            let found: Vec<Vec<u8>> = lines(text.as_bytes()).map(<[u8]>::to_vec).collect();
+            assert_eq!(found, expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_mapped_input() {
+        let path = env::temp_dir().join(format!("aoc-mapped-input-{}.txt", std::process::id()));
+        fs::write(&path, "Card 1: 41 | 41\nCard 2: 13 | 61\n").unwrap();
+        let input = MappedInput::open(&path).unwrap();
+        assert_eq!(input.lines().collect::<Vec<_>>(), vec![&b"Card 1: 41 | 41"[..], b"Card 2: 13 | 61"]);
+        drop(input);
+
+        fs::write(&path, "").unwrap();
+        assert_eq!(MappedInput::open(&path).unwrap().lines().count(), 0);
+        fs::remove_file(&path).unwrap();
+        assert!(MappedInput::open(&path).is_err());
+    }
+}
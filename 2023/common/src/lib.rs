@@ -0,0 +1,11 @@
+pub mod answer;
+pub mod budget;
+pub mod dp;
+pub mod frames;
+pub mod grammar;
+pub mod graph;
+pub mod input;
+pub mod plugin;
+pub mod random;
+pub mod repl;
+pub mod solver;
@@ -0,0 +1,233 @@
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use std::str;
+
+use crate::solver::{Solution, SolveError, Solver, SolverInfo};
+
+/*
+ * The C ABI between the runner and solvers built as shared libraries, so that alternative
+ * solutions can be tried without rebuilding the runner.
+ *
+ * A plugin exports a function named ENTRY_POINT returning its Manifest, which lists its solvers.
+ * Only C types cross the boundary: the input goes in as bytes, and the outcome comes back as
+ * NUL-terminated strings allocated by the plugin, which the runner hands back to the plugin's
+ * free_outcome once it has copied them. Plugins written in Rust get all of this from
+ * export_solvers!, which also catches their panics and reports them as errors.
+ *
+ * The ABI version changes whenever any of these types does, and plugins of another version are
+ * refused.
+ */
+
+pub const ABI_VERSION: u32 = 1;
+
+pub const ENTRY_POINT: &str = "aoc_plugin_manifest";
+
+#[repr(C)]
+pub struct Manifest {
+    pub abi_version: u32,
+    pub solvers: *const SolverEntry,
+    pub solver_count: usize,
+    pub free_outcome: extern "C" fn(Outcome),
+}
+
+#[repr(C)]
+pub struct SolverEntry {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub name: *const c_char,
+    pub solve: extern "C" fn(input: *const u8, length: usize) -> Outcome,
+}
+
+// The pointers in a manifest are to static data, never written to
+unsafe impl Sync for Manifest {}
+unsafe impl Sync for SolverEntry {}
+
+#[repr(C)]
+pub struct Outcome {
+    pub ok: bool,
+    // The answer if ok, otherwise what went wrong
+    pub text: *mut c_char,
+    // The diagnostics, a line each, or null for none
+    pub diagnostics: *mut c_char,
+}
+
+/*
+ * Exports the functions given, each a fn(&str) -> Result<Solution, SolveError>, as the solvers of
+ * a plugin, e.g.
+ *
+ * common::export_solvers![(2023, 6, 1, "count_ways_by_trial", count_ways_by_trial)];
+ *
+ * The crate must be built as a cdylib.
+ */
+#[macro_export]
+macro_rules! export_solvers {
+    [$(($year:expr, $day:expr, $part:expr, $name:literal, $function:path)),* $(,)?] => {
+        #[no_mangle]
+        pub extern "C" fn aoc_plugin_manifest() -> *const $crate::plugin::Manifest {
+            const SOLVERS: &[$crate::plugin::SolverEntry] = &[$(
+                $crate::plugin::SolverEntry {
+                    year: $year,
+                    day: $day,
+                    part: $part,
+                    name: concat!($name, "\0").as_ptr() as *const ::std::ffi::c_char,
+                    solve: {
+                        extern "C" fn solve(input: *const u8, length: usize) -> $crate::plugin::Outcome {
+                            // SAFETY: the runner passes the input it owns for the length of the call
+                            let input = unsafe { ::std::slice::from_raw_parts(input, length) };
+                            $crate::plugin::solve_with($function, input)
+                        }
+                        solve
+                    },
+                },
+            )*];
+            static MANIFEST: $crate::plugin::Manifest = $crate::plugin::Manifest {
+                abi_version: $crate::plugin::ABI_VERSION,
+                solvers: SOLVERS.as_ptr(),
+                solver_count: SOLVERS.len(),
+                free_outcome: $crate::plugin::free_outcome,
+            };
+            &MANIFEST
+        }
+    };
+}
+
+fn c_string(text: String) -> *mut c_char {
+    CString::new(text.replace('\0', "")).expect("NULs were removed").into_raw()
+}
+
+/*
+ * Runs a solver on the input passed over the ABI, for export_solvers!.
+ */
+pub fn solve_with(function: fn(&str) -> Result<Solution, SolveError>, input: &[u8]) -> Outcome {
+    let result = match str::from_utf8(input) {
+        Ok(input) => panic::catch_unwind(AssertUnwindSafe(|| function(input)))
+            .unwrap_or_else(|_| Err(SolveError::new("solver panicked"))),
+        Err(_) => Err(SolveError::new("input is not UTF-8")),
+    };
+    match result {
+        Ok(solution) => Outcome {
+            ok: true,
+            text: c_string(solution.answer),
+            diagnostics: match solution.diagnostics.as_slice() {
+                [] => ptr::null_mut(),
+                lines => c_string(lines.join("\n")),
+            },
+        },
+        Err(error) => Outcome { ok: false, text: c_string(error.0), diagnostics: ptr::null_mut() },
+    }
+}
+
+/*
+ * Frees the strings of an outcome, in the plugin that allocated them.
+ */
+pub extern "C" fn free_outcome(outcome: Outcome) {
+    for text in [outcome.text, outcome.diagnostics] {
+        if !text.is_null() {
+            // SAFETY: every non-null string in an outcome came from CString::into_raw in c_string
+            drop(unsafe { CString::from_raw(text) });
+        }
+    }
+}
+
+/*
+ * A solver of a plugin, seen from the runner.
+ */
+pub struct ForeignSolver {
+    info: SolverInfo,
+    solve: extern "C" fn(*const u8, usize) -> Outcome,
+    free_outcome: extern "C" fn(Outcome),
+}
+
+/**
+ * The solvers listed in a plugin's manifest, or why they cannot be used.
+ *
+ * # Safety
+ * The manifest must follow this ABI, and the library it came from must stay loaded for as long as
+ * the solvers are used.
+ */
+pub unsafe fn foreign_solvers(manifest: &Manifest) -> Result<Vec<ForeignSolver>, String> {
+    if manifest.abi_version != ABI_VERSION {
+        return Err(format!("plugin ABI version {}, expected {}", manifest.abi_version, ABI_VERSION));
+    }
+    let entries = match manifest.solver_count {
+        0 => &[],
+        count => unsafe { slice::from_raw_parts(manifest.solvers, count) },
+    };
+    entries
+        .iter()
+        .map(|entry| {
+            let name = unsafe { CStr::from_ptr(entry.name) }
+                .to_str()
+                .map_err(|_| "solver name is not UTF-8".to_string())?;
+            Ok(ForeignSolver {
+                info: SolverInfo { year: entry.year, day: entry.day, part: entry.part, name: name.to_string() },
+                solve: entry.solve,
+                free_outcome: manifest.free_outcome,
+            })
+        })
+        .collect()
+}
+
+impl Solver for ForeignSolver {
+    fn info(&self) -> &SolverInfo {
+        &self.info
+    }
+
+    fn solve(&self, input: &str) -> Result<Solution, SolveError> {
+        let outcome = (self.solve)(input.as_ptr(), input.len());
+        let read = |text: *mut c_char| if text.is_null() {
+            String::new()
+        } else {
+            // SAFETY: the plugin returns NUL-terminated strings, freed only by free_outcome below
+            unsafe { CStr::from_ptr(text) }.to_string_lossy().into_owned()
+        };
+        let (ok, text, diagnostics) = (outcome.ok, read(outcome.text), read(outcome.diagnostics));
+        (self.free_outcome)(outcome);
+        if ok {
+            Ok(Solution { answer: text, diagnostics: diagnostics.lines().map(str::to_string).collect() })
+        } else {
+            Err(SolveError(text))
+        }
+    }
+}
+
+#[cfg(test)]
+mod plugin_tests {
+    use super::*;
+
+    fn count_lines(input: &str) -> Result<Solution, SolveError> {
+        match input.lines().count() {
+            0 => Err(SolveError::new("no lines")),
+            count => Ok(Solution::new(count, input.lines().map(str::to_uppercase).collect())),
+        }
+    }
+
+    fn panics(_: &str) -> Result<Solution, SolveError> {
+        panic!("as it should")
+    }
+
+    crate::export_solvers![(2023, 1, 1, "count_lines", count_lines), (2023, 1, 2, "panics", panics)];
+
+    #[test]
+    fn test_foreign_solvers() {
+        let solvers = unsafe { foreign_solvers(&*aoc_plugin_manifest()) }.unwrap();
+        let infos: Vec<String> = solvers.iter().map(|solver| solver.info().to_string()).collect();
+        assert_eq!(infos, ["2023/1/1 (count_lines)", "2023/1/2 (panics)"]);
+
+        assert_eq!(solvers[0].solve("a\nb\n"), Ok(Solution::new(2, vec!["A".to_string(), "B".to_string()])));
+        assert_eq!(solvers[0].solve(""), Err(SolveError::new("no lines")));
+        assert_eq!(solvers[1].solve("a"), Err(SolveError::new("solver panicked")));
+    }
+
+    #[test]
+    fn test_abi_version_checked() {
+        let manifest = Manifest { abi_version: ABI_VERSION + 1, solvers: ptr::null(), solver_count: 0, free_outcome };
+        assert_eq!(
+            unsafe { foreign_solvers(&manifest) }.err(),
+            Some(format!("plugin ABI version {}, expected {}", ABI_VERSION + 1, ABI_VERSION)),
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use std::ops::Range;
+
+/*
+ * A small deterministic random number generator (SplitMix64), for generating puzzle inputs that
+ * can be reproduced from the seed they were generated with. Not for anything that needs to be
+ * unpredictable.
+ */
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /*
+     * A number in the range, which must not be empty. Slightly biased towards its start unless
+     * its length is a power of two, which does not matter for generating inputs.
+     */
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "empty range {:?}", range);
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    /*
+     * True one time in n, on average.
+     */
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.range(0..n) == 0
+    }
+}
+
+#[cfg(test)]
+mod random_tests {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        // The first outputs for seed 0 given with the reference implementation
+        let mut rng = Rng::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+
+        let mut rng = Rng::new(2023);
+        let values: Vec<u64> = (0..1000).map(|_| rng.range(5..8)).collect();
+        assert!(values.iter().all(|value| (5..8).contains(value)));
+        assert!((5..8).all(|value| values.contains(&value)));
+        assert_eq!(Rng::new(2023).range(5..8), values[0]);
+    }
+}
@@ -0,0 +1,108 @@
+use std::io::{self, BufRead, Write};
+
+/*
+ * Interactive exploration of a day's input, parsed once and then queried with commands, e.g. for
+ * day 5:
+ *
+ * > lookup seed 79
+ * seed 79 -> soil 81 -> fertilizer 81 -> water 81 -> light 74 -> temperature 78 -> humidity 78 -> location 82
+ *
+ * Each day that supports it implements Explorer over its parsed input, and run() reads commands
+ * for it a line at a time.
+ */
+
+pub trait Explorer {
+    /*
+     * The commands understood, as (usage, description).
+     */
+    fn commands(&self) -> &'static [(&'static str, &'static str)];
+
+    /*
+     * Runs the command, given as its name and arguments, returning the text to show or what was
+     * wrong with the command.
+     */
+    fn command(&mut self, name: &str, args: &[&str]) -> Result<String, String>;
+}
+
+/*
+ * Parses an argument, naming it in the error if it does not parse.
+ */
+pub fn argument<T: std::str::FromStr>(args: &[&str], index: usize, name: &str) -> Result<T, String> {
+    let arg = args.get(index).ok_or_else(|| format!("expected {}", name))?;
+    arg.parse().map_err(|_| format!("{} is not a valid {}", arg, name))
+}
+
+fn help(explorer: &dyn Explorer) -> String {
+    let commands = explorer.commands().iter().chain(&[("help", "show this help"), ("quit", "leave")]);
+    let width = commands.clone().map(|(usage, _)| usage.len()).max().unwrap_or(0);
+    commands.map(|(usage, description)| format!("{:width$}  {}\n", usage, description, width = width)).collect()
+}
+
+/*
+ * Reads commands from input until it ends or the user quits, writing what each gives to output.
+ * A command that fails is reported and the session carries on. With a prompt, each command is
+ * prompted for.
+ */
+pub fn run(explorer: &mut dyn Explorer, input: impl BufRead, mut output: impl Write, prompt: bool) -> io::Result<()> {
+    let mut lines = input.lines();
+    loop {
+        if prompt {
+            write!(output, "> ")?;
+            output.flush()?;
+        }
+        let Some(line) = lines.next().transpose()? else {
+            break;
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let text = match words.as_slice() {
+            [] => continue,
+            ["quit" | "exit"] => break,
+            ["help"] => help(explorer),
+            [name, args @ ..] => match explorer.command(name, args) {
+                Ok(text) => text,
+                Err(message) => format!("error: {}\n", message),
+            },
+        };
+        write!(output, "{}", text)?;
+        if !text.is_empty() && !text.ends_with('\n') {
+            writeln!(output)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod repl_tests {
+    use super::*;
+
+    struct Squares;
+
+    impl Explorer for Squares {
+        fn commands(&self) -> &'static [(&'static str, &'static str)] {
+            &[("square N", "square a number")]
+        }
+
+        fn command(&mut self, name: &str, args: &[&str]) -> Result<String, String> {
+            match name {
+                "square" => argument::<i64>(args, 0, "number").map(|n| (n * n).to_string()),
+                _ => Err(format!("unknown command {}, try help", name)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let input = "square 3\n\nsquare x\nsquare\ncube 2\nhelp\nquit\nsquare 4\n";
+        let mut output = Vec::new();
+        run(&mut Squares, input.as_bytes(), &mut output, false).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "\
+9
+error: x is not a valid number
+error: expected number
+error: unknown command cube, try help
+square N  square a number
+help      show this help
+quit      leave
+");
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+use std::io;
+
+use crate::answer::Overflow;
+use crate::budget::Cancelled;
+
+/*
+ * What a solver produces: the answer, plus the trace lines it would otherwise have printed along
+ * the way (e.g. the value found for each line of input).
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub answer: String,
+    pub diagnostics: Vec<String>,
+}
+
+impl Solution {
+    pub fn new(answer: impl fmt::Display, diagnostics: Vec<String>) -> Self {
+        Solution { answer: answer.to_string(), diagnostics }
+    }
+
+    pub fn print_diagnostics(&self) {
+        for line in &self.diagnostics {
+            println!("{}", line);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError(pub String);
+
+impl SolveError {
+    pub fn new(message: impl Into<String>) -> Self {
+        SolveError(message.into())
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<Overflow> for SolveError {
+    fn from(overflow: Overflow) -> Self {
+        SolveError(overflow.to_string())
+    }
+}
+
+impl From<Cancelled> for SolveError {
+    fn from(cancelled: Cancelled) -> Self {
+        SolveError(cancelled.to_string())
+    }
+}
+
+impl From<io::Error> for SolveError {
+    fn from(error: io::Error) -> Self {
+        SolveError(error.to_string())
+    }
+}
+
+impl From<SolveError> for io::Error {
+    fn from(error: SolveError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+/*
+ * Identifies a solver: the puzzle part it solves, and a name to tell apart several solvers for the
+ * same part.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SolverInfo {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub name: String,
+}
+
+impl fmt::Display for SolverInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{} ({})", self.year, self.day, self.part, self.name)
+    }
+}
+
+/*
+ * A solution to one part of one day's puzzle, taking the whole puzzle input as text.
+ */
+pub trait Solver: Send + Sync {
+    fn info(&self) -> &SolverInfo;
+
+    fn solve(&self, input: &str) -> Result<Solution, SolveError>;
+}
+
+/*
+ * Solver implemented by a plain function, as the built-in days are.
+ */
+pub struct FnSolver {
+    info: SolverInfo,
+    function: fn(&str) -> Result<Solution, SolveError>,
+}
+
+impl FnSolver {
+    pub fn new(year: u16, day: u8, part: u8, name: &str, function: fn(&str) -> Result<Solution, SolveError>) -> Self {
+        FnSolver { info: SolverInfo { year, day, part, name: name.to_string() }, function }
+    }
+}
+
+impl Solver for FnSolver {
+    fn info(&self) -> &SolverInfo {
+        &self.info
+    }
+
+    fn solve(&self, input: &str) -> Result<Solution, SolveError> {
+        (self.function)(input)
+    }
+}
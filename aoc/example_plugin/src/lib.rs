@@ -0,0 +1,29 @@
+use common::solver::{Solution, SolveError};
+
+/*
+ * An alternative solver for day 6, as a plugin: it tries every time the button could be held
+ * for, rather than solving the quadratic.
+ */
+
+fn count_ways_by_trial(input: &str) -> Result<Solution, SolveError> {
+    let mut lines = input.lines();
+    let (Some(times), Some(distances)) = (lines.next(), lines.next()) else {
+        return Err(SolveError::new("expected lines of times and distances"));
+    };
+    let times = day6::numbers_from_string(times);
+    let distances = day6::numbers_from_string(distances);
+
+    let mut diagnostics = Vec::new();
+    let mut product: u64 = 1;
+    for (time, distance) in times.into_iter().zip(distances) {
+        let ways = (0..=time).filter(|held| held * (time - held) > distance).count() as u64;
+        diagnostics.push(format!("{} {} {}", time, distance, ways));
+        product = product.checked_mul(ways).ok_or_else(|| SolveError::new("overflow while multiplying counts of ways"))?;
+    }
+    Ok(Solution::new(product, diagnostics))
+}
+
+common::export_solvers![
+    (2023, 6, 1, "count_ways_by_trial", count_ways_by_trial),
+    (2023, 6, 2, "count_ways_by_trial", count_ways_by_trial),
+];
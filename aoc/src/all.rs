@@ -0,0 +1,266 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::budget::Budget;
+use common::solver::{Solver, SolverInfo};
+
+use crate::history::{Answers, HistoryEntry};
+use crate::registry::Registry;
+
+/*
+ * Runs every solver registered for a year, in parallel, and compares their answers with those
+ * recorded.
+ */
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    // The answer matches the one recorded
+    Verified,
+    // There is no answer recorded to compare with
+    New,
+    Wrong { expected: String },
+    Failed(String),
+    InvalidInput(usize),
+    NoInput,
+}
+
+impl Status {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Status::Wrong { .. } | Status::Failed(_) | Status::InvalidInput(_))
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Status::Verified => "ok".to_string(),
+            Status::New => "new".to_string(),
+            Status::Wrong { expected } => format!("WRONG (expected {})", expected),
+            Status::Failed(message) => format!("FAILED ({})", message),
+            Status::InvalidInput(count) => format!("INVALID INPUT ({} violation(s))", count),
+            Status::NoInput => "no input".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub info: SolverInfo,
+    pub answer: Option<String>,
+    pub status: Status,
+    pub elapsed: Duration,
+}
+
+impl Outcome {
+    /*
+     * The outcome as an entry for the history, unless the solver never ran.
+     */
+    pub fn history_entry(&self, time: u64) -> Option<HistoryEntry> {
+        let status = match &self.status {
+            Status::Verified => "ok",
+            Status::New => "new",
+            Status::Wrong { .. } => "wrong",
+            Status::Failed(_) => "failed",
+            Status::InvalidInput(_) | Status::NoInput => return None,
+        };
+        Some(HistoryEntry {
+            year: self.info.year,
+            day: self.info.day,
+            part: self.info.part,
+            name: self.info.name.clone(),
+            answer: self.answer.clone(),
+            status: status.to_string(),
+            elapsed_ms: self.elapsed.as_secs_f64() * 1000.0,
+            time,
+        })
+    }
+}
+
+fn run_one(registry: &Registry, solver: &dyn Solver, input: Result<Option<String>, String>, answers: &Answers, budget: &Budget) -> Outcome {
+    let info = solver.info().clone();
+    let outcome = |answer, status, elapsed| Outcome { info: info.clone(), answer, status, elapsed };
+
+    let input = match input {
+        Ok(Some(input)) => input,
+        Ok(None) => return outcome(None, Status::NoInput, Duration::ZERO),
+        Err(message) => return outcome(None, Status::Failed(message), Duration::ZERO),
+    };
+    if let Some(grammar) = registry.grammar(info.year, info.day) {
+        let violations = grammar.validate(&input).len();
+        if violations > 0 {
+            return outcome(None, Status::InvalidInput(violations), Duration::ZERO);
+        }
+    }
+
+    let start = Instant::now();
+    let guard = budget.install();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| solver.solve(&input)));
+    drop(guard);
+    let elapsed = start.elapsed();
+
+    match result {
+        Ok(Ok(solution)) => {
+            let status = match answers.get(info.year, info.day, info.part) {
+                Some(expected) if expected == solution.answer => Status::Verified,
+                Some(expected) => Status::Wrong { expected: expected.to_string() },
+                None => Status::New,
+            };
+            outcome(Some(solution.answer), status, elapsed)
+        }
+        Ok(Err(error)) => outcome(None, Status::Failed(error.to_string()), elapsed),
+        Err(_) => outcome(None, Status::Failed("solver panicked".to_string()), elapsed),
+    }
+}
+
+/*
+ * Runs each solver registered for the year (or for every year) on the input that input_for gives
+ * it (None if it has none), spread over the available cores. Reference solvers are left out, as
+ * they are too slow for real inputs. Outcomes are in the order the solvers
+ * were registered.
+ */
+pub fn run_all(
+    registry: &Registry,
+    year: Option<u16>,
+    input_for: impl Fn(&SolverInfo) -> Result<Option<String>, String> + Sync,
+    answers: &Answers,
+    budget: &Budget,
+) -> Vec<Outcome> {
+    let solvers: Vec<&dyn Solver> = registry.solvers()
+        .filter(|solver| year.is_none_or(|year| solver.info().year == year) && !registry.is_reference(solver.info()))
+        .collect();
+    let workers = thread::available_parallelism().map_or(1, |n| n.get()).min(solvers.len().max(1));
+    let next = AtomicUsize::new(0);
+    let outcomes = Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&solver) = solvers.get(index) else { break };
+                let input = input_for(solver.info());
+                let outcome = run_one(registry, solver, input, answers, budget);
+                outcomes.lock().unwrap().push((index, outcome));
+            });
+        }
+    });
+
+    let mut outcomes = outcomes.into_inner().unwrap();
+    outcomes.sort_by_key(|&(index, _)| index);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+/*
+ * Formats the outcomes as a table, one row per solver.
+ */
+pub fn summary_table(outcomes: &[Outcome]) -> String {
+    let header = ["Puzzle", "Solver", "Answer", "Time", "Status"].map(str::to_string);
+    let rows = outcomes.iter().map(|outcome| [
+        format!("{}/{}/{}", outcome.info.year, outcome.info.day, outcome.info.part),
+        outcome.info.name.clone(),
+        outcome.answer.clone().unwrap_or_else(|| "-".to_string()),
+        if outcome.elapsed.is_zero() { "-".to_string() } else { format!("{:.3}ms", outcome.elapsed.as_secs_f64() * 1000.0) },
+        outcome.status.label(),
+    ]);
+    format_table(header, rows)
+}
+
+/*
+ * Lays out rows of cells in columns as wide as their widest cell, under the header.
+ */
+pub fn format_table<const N: usize>(header: [String; N], rows: impl IntoIterator<Item = [String; N]>) -> String {
+    let rows: Vec<[String; N]> = rows.into_iter().collect();
+    let mut widths = header.clone().map(|cell| cell.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    std::iter::once(header)
+        .chain(rows)
+        .map(|row| {
+            let cells: Vec<String> = row.iter().zip(widths).map(|(cell, width)| format!("{:width$}", cell, width = width)).collect();
+            cells.join("  ").trim_end().to_string() + "\n"
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod all_tests {
+    use super::*;
+
+    fn example(info: &SolverInfo) -> Result<Option<String>, String> {
+        let input = match info.day {
+            1 if info.part == 1 => include_str!("../../2023/1/example_calibration_document.txt"),
+            1 => include_str!("../../2023/1/example_calibration_document_2.txt"),
+            2 => include_str!("../../2023/2/example_record.txt"),
+            3 => include_str!("../../2023/3/example_schematic.txt"),
+            4 => include_str!("../../2023/4/example_cards.txt"),
+            5 => include_str!("../../2023/5/example_almanac.txt"),
+            6 if info.part == 1 => "Time:      7  15   30\nDistance:  9  40  200\n",
+            _ => return Ok(None),
+        };
+        Ok(Some(input.to_string()))
+    }
+
+    #[test]
+    fn test_run_all() {
+        let registry = Registry::builtin();
+        let mut answers = Answers::default();
+        answers.insert(2023, 2, 1, "8");
+        answers.insert(2023, 4, 2, "31");
+
+        let outcomes = run_all(&registry, Some(2023), example, &answers, &Budget::default());
+        let summary: Vec<(String, Option<&str>, Status)> = outcomes.iter()
+            .map(|outcome| (outcome.info.to_string(), outcome.answer.as_deref(), outcome.status.clone()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("2023/1/1 (sum_calibration_values)".to_string(), Some("142"), Status::New),
+            ("2023/1/2 (sum_calibration_values)".to_string(), Some("281"), Status::New),
+            ("2023/2/1 (sum_games)".to_string(), Some("8"), Status::Verified),
+            ("2023/2/2 (sum_powers)".to_string(), Some("2286"), Status::New),
+            ("2023/3/1 (sum_parts)".to_string(), Some("4361"), Status::New),
+            ("2023/3/2 (sum_gears)".to_string(), Some("467835"), Status::New),
+            ("2023/4/1 (sum_points)".to_string(), Some("13"), Status::New),
+            ("2023/4/2 (sum_cards)".to_string(), Some("30"), Status::Wrong { expected: "31".to_string() }),
+            ("2023/5/2 (map_seeds)".to_string(), Some("46"), Status::New),
+            ("2023/6/1 (product_of_ways_count)".to_string(), Some("288"), Status::New),
+            ("2023/6/2 (product_of_ways_count)".to_string(), None, Status::NoInput),
+        ]);
+        assert_eq!(outcomes.iter().filter(|outcome| outcome.status.is_failure()).count(), 1);
+        assert!(run_all(&registry, Some(2022), example, &answers, &Budget::default()).is_empty());
+    }
+
+    #[test]
+    fn test_failures() {
+        let registry = Registry::builtin();
+        let input_for = |info: &SolverInfo| match (info.day, info.part) {
+            (4, 1) => Ok(Some("Card 1 41 | 83\n".to_string())),
+            (6, 1) => Err("cannot decrypt input".to_string()),
+            _ => Ok(None),
+        };
+        let outcomes = run_all(&registry, Some(2023), input_for, &Answers::default(), &Budget::default());
+        let failures: Vec<String> = outcomes.iter()
+            .filter(|outcome| outcome.status.is_failure())
+            .map(|outcome| format!("{}: {}", outcome.info, outcome.status.label()))
+            .collect();
+        assert_eq!(failures, vec![
+            "2023/4/1 (sum_points): INVALID INPUT (1 violation(s))",
+            "2023/6/1 (product_of_ways_count): FAILED (cannot decrypt input)",
+        ]);
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let info = SolverInfo { year: 2023, day: 6, part: 1, name: "product_of_ways_count".to_string() };
+        let outcomes = [
+            Outcome { info: info.clone(), answer: Some("288".to_string()), status: Status::Verified, elapsed: Duration::from_micros(1500) },
+            Outcome { info: SolverInfo { part: 2, ..info }, answer: None, status: Status::NoInput, elapsed: Duration::ZERO },
+        ];
+        assert_eq!(summary_table(&outcomes), "\
+Puzzle    Solver                 Answer  Time     Status
+2023/6/1  product_of_ways_count  288     1.500ms  ok
+2023/6/2  product_of_ways_count  -       -        no input
+");
+    }
+}
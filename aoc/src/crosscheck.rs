@@ -0,0 +1,287 @@
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+
+use common::random::Rng;
+use common::solver::Solver;
+
+use crate::registry::Registry;
+
+/*
+ * Checks the solvers of each part against each other, including any brute-force reference, on
+ * inputs generated for the day. An input they disagree on is minimised before it is reported:
+ * lines and numbers are taken out, and numbers made smaller, for as long as the input still
+ * follows the day's grammar and the solvers still disagree on it.
+ */
+
+/*
+ * What one solver made of an input: its answer, or its error (a panic included).
+ */
+pub type Answer = Result<String, String>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    // The seed and size the input was generated with, to reproduce it
+    pub seed: u64,
+    pub size: usize,
+    pub input: String,
+    // Each solver's name and answer, for the minimised input
+    pub answers: Vec<(String, Answer)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartReport {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub solvers: Vec<String>,
+    // How many inputs were tried, up to and including any disagreed on
+    pub inputs: usize,
+    pub disagreement: Option<Disagreement>,
+}
+
+fn answer(solver: &dyn Solver, input: &str) -> Answer {
+    match panic::catch_unwind(AssertUnwindSafe(|| solver.solve(input))) {
+        Ok(Ok(solution)) => Ok(solution.answer),
+        Ok(Err(error)) => Err(error.to_string()),
+        Err(_) => Err("panicked".to_string()),
+    }
+}
+
+/*
+ * Whether the answers, each with whether its solver is a reference, disagree: the references all
+ * answered (or if there are none, at least one solver did), and not all alike. A reference
+ * rejecting an input means it is not one the puzzle could give, so it does not count.
+ */
+fn disagree(answers: &[(bool, Answer)]) -> bool {
+    let trusted = if answers.iter().any(|&(reference, _)| reference) {
+        answers.iter().filter(|&&(reference, _)| reference).all(|(_, answer)| answer.is_ok())
+    } else {
+        answers.iter().any(|(_, answer)| answer.is_ok())
+    };
+    trusted && answers.iter().any(|(_, answer)| answer != &answers[0].1)
+}
+
+fn number_spans(input: &str) -> Vec<Range<usize>> {
+    let bytes = input.as_bytes();
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, byte) in bytes.iter().chain(b"\n").enumerate() {
+        match (start, byte.is_ascii_digit()) {
+            (None, true) => start = Some(i),
+            (Some(from), false) => {
+                spans.push(from..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+/*
+ * The span of a number (or numbers) along with the spaces before it, or if it starts a line, the
+ * spaces after.
+ */
+fn with_spaces(input: &str, span: &Range<usize>) -> Range<usize> {
+    let before = input[..span.start].bytes().rev().take_while(|&byte| byte == b' ').count();
+    let starts_line = span.start == before || input.as_bytes()[span.start - before - 1] == b'\n';
+    if starts_line {
+        span.start..span.end + input[span.end..].bytes().take_while(|&byte| byte == b' ').count()
+    } else {
+        span.start - before..span.end
+    }
+}
+
+/*
+ * Smaller variants of an input, tried in turn while minimising: without one of its lines, without
+ * one or two neighbouring numbers of a line, or with one of its numbers made smaller.
+ */
+fn shrink_candidates(input: &str) -> Vec<String> {
+    let mut candidates = Vec::new();
+    let without = |range: Range<usize>| format!("{}{}", &input[..range.start], &input[range.end..]);
+
+    let lines: Vec<&str> = input.split_inclusive('\n').collect();
+    for i in 0..lines.len() {
+        candidates.push([&lines[..i], &lines[i + 1..]].concat().concat());
+    }
+
+    let spans = number_spans(input);
+    for pair in spans.windows(2) {
+        if input[pair[0].end..pair[1].start].bytes().all(|byte| byte == b' ') {
+            candidates.push(without(with_spaces(input, &(pair[0].start..pair[1].end))));
+        }
+    }
+    for span in &spans {
+        candidates.push(without(with_spaces(input, span)));
+    }
+    for span in &spans {
+        let Ok(number) = input[span.clone()].parse::<u64>() else {
+            continue;
+        };
+        let mut smaller = vec![0, number / 2, number.saturating_sub(1)];
+        smaller.dedup();
+        for value in smaller.into_iter().filter(|&value| value < number) {
+            candidates.push(format!("{}{}{}", &input[..span.start], value, &input[span.end..]));
+        }
+    }
+    candidates
+}
+
+/*
+ * Shrinks an input for as long as any smaller variant of it still fails. Variants are tried from
+ * where the last one that failed was found, as those before it are likely still to pass.
+ */
+pub fn minimise(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut input = input.to_string();
+    let mut start = 0;
+    loop {
+        let mut candidates = shrink_candidates(&input);
+        let start_at = start.min(candidates.len());
+        match (start_at..candidates.len()).chain(0..start_at).find(|&i| fails(&candidates[i])) {
+            Some(i) => {
+                input = candidates.swap_remove(i);
+                start = i;
+            }
+            None => return input,
+        }
+    }
+}
+
+/*
+ * Crosschecks every part with a generator and more than one solver, in the year and day given (or
+ * all of them), on count inputs growing from size 1 to max_size. The inputs are generated from
+ * consecutive seeds starting at seed. A part's checking stops at the first disagreement.
+ */
+pub fn crosscheck(
+    registry: &Registry,
+    year: Option<u16>,
+    day: Option<u8>,
+    count: usize,
+    seed: u64,
+    max_size: usize,
+) -> Vec<PartReport> {
+    // Panics are reported as answers, so their messages would only get in the way
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut reports = Vec::new();
+    for (y, d, generate) in registry.generators() {
+        if year.is_some_and(|year| year != y) || day.is_some_and(|day| day != d) {
+            continue;
+        }
+        for part in 1..=2 {
+            let solvers: Vec<&dyn Solver> = registry.solvers()
+                .filter(|solver| (solver.info().year, solver.info().day, solver.info().part) == (y, d, part))
+                .collect();
+            if solvers.len() < 2 {
+                continue;
+            }
+            let answers = |input: &str| -> Vec<(bool, Answer)> {
+                solvers.iter().map(|&solver| (registry.is_reference(solver.info()), answer(solver, input))).collect()
+            };
+            let fails = |input: &str| {
+                registry.grammar(y, d).is_none_or(|grammar| grammar.validate(input).is_empty()) && disagree(&answers(input))
+            };
+
+            let mut report = PartReport {
+                year: y,
+                day: d,
+                part,
+                solvers: solvers.iter().map(|solver| solver.info().name.clone()).collect(),
+                inputs: 0,
+                disagreement: None,
+            };
+            for i in 0..count {
+                let seed = seed.wrapping_add(i as u64);
+                let size = 1 + i * max_size / count;
+                let input = generate(&mut Rng::new(seed), size);
+                report.inputs += 1;
+                if fails(&input) {
+                    let input = minimise(&input, fails);
+                    let answers = report.solvers.iter().cloned().zip(answers(&input).into_iter().map(|(_, answer)| answer)).collect();
+                    report.disagreement = Some(Disagreement { seed, size, input, answers });
+                    break;
+                }
+            }
+            reports.push(report);
+        }
+    }
+
+    panic::set_hook(hook);
+    reports
+}
+
+#[cfg(test)]
+mod crosscheck_tests {
+    use super::*;
+    use common::solver::{FnSolver, Solution, SolveError};
+
+    fn numbers(input: &str) -> Vec<u64> {
+        input.split_whitespace().filter_map(|word| word.parse().ok()).collect()
+    }
+
+    fn sum(input: &str) -> Result<Solution, SolveError> {
+        Ok(Solution::new(numbers(input).iter().sum::<u64>(), Vec::new()))
+    }
+
+    // Wrong once any number is over 50
+    fn capped_sum(input: &str) -> Result<Solution, SolveError> {
+        Ok(Solution::new(numbers(input).iter().map(|&number| number.min(50)).sum::<u64>(), Vec::new()))
+    }
+
+    fn panicking_sum(input: &str) -> Result<Solution, SolveError> {
+        assert!(numbers(input).len() < 3);
+        sum(input)
+    }
+
+    fn generate_numbers(rng: &mut Rng, size: usize) -> String {
+        (0..rng.range(1..4)).map(|_| format!("{}\n", rng.range(0..10 * size as u64))).collect()
+    }
+
+    #[test]
+    fn test_minimise() {
+        let fails = |input: &str| numbers(input).iter().any(|&number| number >= 10);
+        assert_eq!(minimise("a 3\nb 25 7\nc 40\n", fails), "c 10\n");
+        assert_eq!(minimise("1 2 3\n", |input: &str| numbers(input).len() > 1), "0 0\n");
+    }
+
+    #[test]
+    fn test_disagree() {
+        let (one, two, error) = (Ok("1".to_string()), Ok("2".to_string()), Err("rejected".to_string()));
+        assert!(disagree(&[(false, one.clone()), (false, two.clone())]));
+        assert!(disagree(&[(false, one.clone()), (false, error.clone())]));
+        assert!(!disagree(&[(false, error.clone()), (false, error.clone())]));
+        assert!(disagree(&[(false, one.clone()), (true, two.clone())]));
+        assert!(!disagree(&[(false, one.clone()), (true, error.clone())]), "the reference rejects the input");
+        assert!(!disagree(&[(false, one.clone()), (true, one.clone())]));
+    }
+
+    #[test]
+    fn test_crosscheck() {
+        let mut registry = Registry::new();
+        registry.register(FnSolver::new(2000, 1, 1, "sum", sum));
+        registry.register(FnSolver::new(2000, 1, 1, "capped_sum", capped_sum));
+        registry.register(FnSolver::new(2000, 1, 2, "sum", sum));
+        registry.register(FnSolver::new(2000, 1, 2, "panicking_sum", panicking_sum));
+        registry.register(FnSolver::new(2000, 2, 1, "sum", sum));
+        registry.register_reference(FnSolver::new(2000, 2, 1, "also_sum", sum));
+        registry.register(FnSolver::new(2000, 3, 1, "sum", sum));
+        registry.register_generator(2000, 1, generate_numbers);
+        registry.register_generator(2000, 2, generate_numbers);
+        registry.register_generator(2000, 3, generate_numbers);
+
+        let reports = crosscheck(&registry, Some(2000), None, 50, 0, 20);
+        let parts: Vec<(u8, u8, usize)> = reports.iter().map(|report| (report.day, report.part, report.inputs)).collect();
+        assert_eq!(parts[2], (2, 1, 50));
+        assert_eq!(parts.len(), 3, "day 3 has only one solver");
+
+        let capped = reports[0].disagreement.as_ref().unwrap();
+        assert_eq!(capped.input, "51\n");
+        assert_eq!(capped.answers, [("sum".to_string(), Ok("51".to_string())), ("capped_sum".to_string(), Ok("50".to_string()))]);
+
+        let panicking = reports[1].disagreement.as_ref().unwrap();
+        assert_eq!(panicking.input, "0\n0\n0\n");
+        assert_eq!(panicking.answers[1], ("panicking_sum".to_string(), Err("panicked".to_string())));
+        assert_eq!(reports[2].disagreement, None);
+    }
+}
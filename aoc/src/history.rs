@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+/*
+ * Answers recorded for each part, and the history of runs, kept beside the inputs they are for.
+ *
+ * answers.json maps "YEAR/DAY/PART" to the answer for the stored input, and is committed along
+ * with the inputs. history.jsonl holds a line of JSON per run, and stays local.
+ */
+
+fn invalid(path: &Path, message: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), message))
+}
+
+pub fn answers_path(inputs_dir: &Path) -> PathBuf {
+    inputs_dir.join("answers.json")
+}
+
+pub fn history_path(inputs_dir: &Path) -> PathBuf {
+    inputs_dir.join("history.jsonl")
+}
+
+fn key(year: u16, day: u8, part: u8) -> String {
+    format!("{}/{}/{}", year, day, part)
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers(BTreeMap<String, String>);
+
+impl Answers {
+    /*
+     * Loads the answers recorded at path, or none if there is no file there yet.
+     */
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map(Answers).map_err(|error| invalid(path, error)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let text = serde_json::to_string_pretty(&self.0).map_err(|error| invalid(path, error))?;
+        fs::write(path, text + "\n")
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&key(year, day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, year: u16, day: u8, part: u8, answer: &str) {
+        self.0.insert(key(year, day, part), answer.to_string());
+    }
+
+    /*
+     * Every answer recorded, by year, day and part, skipping any key not of that form.
+     */
+    pub fn iter(&self) -> impl Iterator<Item = ((u16, u8, u8), &str)> {
+        self.0.iter().filter_map(|(key, answer)| {
+            let mut fields = key.split('/');
+            let puzzle = (fields.next()?.parse().ok()?, fields.next()?.parse().ok()?, fields.next()?.parse().ok()?);
+            fields.next().is_none().then_some((puzzle, answer.as_str()))
+        })
+    }
+}
+
+/*
+ * One run of a solver. The answer is missing if the run failed.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub name: String,
+    pub answer: Option<String>,
+    pub status: String,
+    pub elapsed_ms: f64,
+    // Seconds since the Unix epoch
+    pub time: u64,
+}
+
+impl HistoryEntry {
+    pub fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "year": self.year,
+            "day": self.day,
+            "part": self.part,
+            "name": self.name,
+            "answer": self.answer,
+            "status": self.status,
+            "elapsed_ms": self.elapsed_ms,
+            "time": self.time,
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        let number = |field: &str| value[field].as_u64();
+        Some(HistoryEntry {
+            year: number("year")?.try_into().ok()?,
+            day: number("day")?.try_into().ok()?,
+            part: number("part")?.try_into().ok()?,
+            name: value["name"].as_str()?.to_string(),
+            answer: match &value["answer"] {
+                Value::Null => None,
+                answer => Some(answer.as_str()?.to_string()),
+            },
+            status: value["status"].as_str()?.to_string(),
+            elapsed_ms: value["elapsed_ms"].as_f64()?,
+            time: number("time")?,
+        })
+    }
+}
+
+pub fn append_history(path: &Path, entries: &[HistoryEntry]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for entry in entries {
+        writeln!(file, "{}", entry.to_json())?;
+    }
+    Ok(())
+}
+
+/*
+ * Loads every run recorded at path, oldest first, or none if there is no file there yet.
+ */
+pub fn load_history(path: &Path) -> io::Result<Vec<HistoryEntry>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .ok()
+                .and_then(|value| HistoryEntry::from_json(&value))
+                .ok_or_else(|| invalid(path, format!("line {}: not a run", i + 1)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_answers_and_history_round_trip() {
+        let dir = env::temp_dir().join(format!("aoc-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(Answers::load(&answers_path(&dir)).unwrap(), Answers::default());
+        let mut answers = Answers::default();
+        answers.insert(2023, 6, 1, "288");
+        answers.save(&answers_path(&dir)).unwrap();
+        let loaded = Answers::load(&answers_path(&dir)).unwrap();
+        assert_eq!(loaded.get(2023, 6, 1), Some("288"));
+        assert_eq!(loaded.get(2023, 6, 2), None);
+        assert_eq!(loaded.iter().collect::<Vec<_>>(), vec![((2023, 6, 1), "288")]);
+
+        let entry = HistoryEntry {
+            year: 2023,
+            day: 6,
+            part: 1,
+            name: "product_of_ways_count".to_string(),
+            answer: Some("288".to_string()),
+            status: "ok".to_string(),
+            elapsed_ms: 0.5,
+            time: 1701820800,
+        };
+        let failed = HistoryEntry { answer: None, status: "failed".to_string(), ..entry.clone() };
+        append_history(&history_path(&dir), std::slice::from_ref(&entry)).unwrap();
+        append_history(&history_path(&dir), std::slice::from_ref(&failed)).unwrap();
+        let lines: Vec<Value> = fs::read_to_string(history_path(&dir)).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[1]["answer"], Value::Null);
+        assert_eq!(load_history(&history_path(&dir)).unwrap(), vec![entry, failed]);
+
+        fs::write(history_path(&dir), "{\"year\": 2023}\n").unwrap();
+        assert!(load_history(&history_path(&dir)).unwrap_err().to_string().ends_with("history.jsonl: line 1: not a run"));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(load_history(&history_path(&dir)).unwrap(), vec![]);
+    }
+}
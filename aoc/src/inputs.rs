@@ -0,0 +1,316 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+/*
+ * Store of personal puzzle inputs, kept encrypted so that they can be committed.
+ *
+ * Each input is a file YEAR/DAY/NAME.enc under the store directory, holding:
+ *
+ * MAGIC | salt (16 bytes) | nonce (12 bytes) | ChaCha20-Poly1305 ciphertext
+ *
+ * The key is derived with Argon2 from the team secret (a passphrase or the contents of a key file)
+ * and the file's own salt.
+ */
+
+const MAGIC: &[u8] = b"aoc-input-1\n";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+// Name of the input used for a day when no other is asked for
+pub const DEFAULT_NAME: &str = "input";
+
+/*
+ * The team secret from which keys are derived.
+ */
+pub struct Secret(Vec<u8>);
+
+impl Secret {
+    pub fn passphrase(passphrase: &str) -> Self {
+        Secret(passphrase.as_bytes().to_vec())
+    }
+
+    pub fn key_file(path: &Path) -> io::Result<Self> {
+        let contents = fs::read(path)?;
+        if contents.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("key file {} is empty", path.display())));
+        }
+        Ok(Secret(contents))
+    }
+
+    /*
+     * Finds the secret from the key file given, else from the environment: AOC_INPUTS_KEY_FILE
+     * names a key file, or AOC_INPUTS_PASSPHRASE holds a passphrase.
+     */
+    pub fn find(key_file: Option<&str>) -> io::Result<Self> {
+        if let Some(path) = key_file.map(PathBuf::from).or_else(|| env::var_os("AOC_INPUTS_KEY_FILE").map(PathBuf::from)) {
+            return Secret::key_file(&path);
+        }
+        match env::var("AOC_INPUTS_PASSPHRASE") {
+            Ok(passphrase) if !passphrase.is_empty() => Ok(Secret::passphrase(&passphrase)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no secret for the input store: pass --key-file, or set AOC_INPUTS_KEY_FILE or AOC_INPUTS_PASSPHRASE",
+            )),
+        }
+    }
+
+    fn cipher(&self, salt: &[u8]) -> io::Result<ChaCha20Poly1305> {
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(&self.0, salt, &mut key)
+            .map_err(|error| io::Error::other(format!("key derivation failed: {}", error)))?;
+        Ok(ChaCha20Poly1305::new(&key))
+    }
+}
+
+pub fn encrypt(secret: &Secret, plaintext: &[u8]) -> io::Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = secret.cipher(&salt)?
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| io::Error::other("encryption failed"))?;
+
+    let mut data = Vec::with_capacity(MAGIC.len() + SALT_LENGTH + NONCE_LENGTH + ciphertext.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+pub fn decrypt(secret: &Secret, data: &[u8]) -> io::Result<Vec<u8>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    let rest = data.strip_prefix(MAGIC).ok_or_else(|| invalid("not an encrypted input"))?;
+    if rest.len() < SALT_LENGTH + NONCE_LENGTH {
+        return Err(invalid("encrypted input is truncated"));
+    }
+    let (salt, rest) = rest.split_at(SALT_LENGTH);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LENGTH);
+    secret.cipher(salt)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| invalid("cannot decrypt input: wrong secret, or the file was modified"))
+}
+
+pub fn is_encrypted(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "enc")
+}
+
+/*
+ * Reads an input file as text, decrypting it first if it is an encrypted one.
+ */
+pub fn read_input(path: &Path, key_file: Option<&str>) -> io::Result<String> {
+    let data = fs::read(path)?;
+    let plaintext = if is_encrypted(path) { decrypt(&Secret::find(key_file)?, &data)? } else { data };
+    String::from_utf8(plaintext).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "input is not valid UTF-8"))
+}
+
+/*
+ * Fails unless path is outside every git work tree, or is ignored by git, so that plain text
+ * written there (an input, or the runs made on it) cannot end up committed. Every command that
+ * writes a file in plain text checks it first. Fails too if git cannot tell, or cannot be run.
+ */
+pub fn refuse_tracked(path: &Path) -> io::Result<()> {
+    refuse_tracked_by("git", path)
+}
+
+fn refuse_tracked_by(git_program: &str, path: &Path) -> io::Result<()> {
+    // Ask git from the nearest directory that exists, about the path relative to it
+    let path = if path.is_absolute() { path.to_path_buf() } else { env::current_dir()?.join(path) };
+    let directory = path.ancestors().skip(1).find(|ancestor| ancestor.is_dir()).unwrap_or(Path::new("/"));
+    let relative = path.strip_prefix(directory).unwrap_or(&path);
+    let refuse = |why: String| {
+        io::Error::new(io::ErrorKind::PermissionDenied, format!("refusing to write plain text to {}, {}", path.display(), why))
+    };
+    // In the C locale, so that git's messages can be recognised
+    let git = |args: &[&str]| {
+        Command::new(git_program).arg("-C").arg(directory).args(args).env("LC_ALL", "C").output()
+            .map_err(|error| refuse(format!("as git cannot be run to tell whether it would be tracked: {}", error)))
+    };
+
+    let output = git(&["rev-parse", "--is-inside-work-tree"])?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        if message.contains("not a git repository") {
+            return Ok(());
+        }
+        return Err(refuse(format!("as git cannot tell whether it would be tracked: {}", message.trim())));
+    }
+    if !output.stdout.starts_with(b"true") {
+        return Ok(());
+    }
+    let output = git(&["check-ignore", "-q", &relative.to_string_lossy()])?;
+    match output.status.code() {
+        Some(0) => Ok(()),
+        Some(1) => Err(refuse("which git would track".to_string())),
+        _ => Err(refuse(format!(
+            "as git cannot tell whether it would be tracked: {}",
+            String::from_utf8_lossy(&output.stderr).trim(),
+        ))),
+    }
+}
+
+/*
+ * Directory of encrypted inputs: AOC_INPUTS_DIR if set, else inputs/ at the top of the repository.
+ */
+pub fn default_dir() -> PathBuf {
+    env::var_os("AOC_INPUTS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("inputs"))
+}
+
+pub struct InputStore {
+    dir: PathBuf,
+}
+
+impl InputStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        InputStore { dir: dir.into() }
+    }
+
+    pub fn path(&self, year: u16, day: u8, name: &str) -> PathBuf {
+        self.dir.join(year.to_string()).join(day.to_string()).join(format!("{}.enc", name))
+    }
+
+    pub fn contains(&self, year: u16, day: u8, name: &str) -> bool {
+        self.path(year, day, name).is_file()
+    }
+
+    pub fn add(&self, secret: &Secret, year: u16, day: u8, name: &str, plaintext: &[u8]) -> io::Result<PathBuf> {
+        let path = self.path(year, day, name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, encrypt(secret, plaintext)?)?;
+        Ok(path)
+    }
+
+    pub fn read(&self, secret: &Secret, year: u16, day: u8, name: &str) -> io::Result<String> {
+        let path = self.path(year, day, name);
+        let data = fs::read(&path).map_err(|error| {
+            io::Error::new(error.kind(), format!("no stored input {}/{}/{}: {}", year, day, name, error))
+        })?;
+        String::from_utf8(decrypt(secret, &data)?)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "input is not valid UTF-8"))
+    }
+
+    /*
+     * Name of the stored input for a part: partN if there is one (e.g. when part 2 needs its
+     * input edited), otherwise the default.
+     */
+    pub fn name_for_part(&self, year: u16, day: u8, part: u8) -> Option<String> {
+        [format!("part{}", part), DEFAULT_NAME.to_string()]
+            .into_iter()
+            .find(|name| self.contains(year, day, name))
+    }
+
+    /*
+     * Lists the stored inputs as (year, day, name), sorted.
+     */
+    pub fn list(&self) -> io::Result<Vec<(u16, u8, String)>> {
+        let mut inputs = Vec::new();
+        if !self.dir.is_dir() {
+            return Ok(inputs);
+        }
+        for year_entry in fs::read_dir(&self.dir)? {
+            let year_entry = year_entry?;
+            let Some(year) = year_entry.file_name().to_str().and_then(|s| s.parse().ok()) else { continue };
+            for day_entry in fs::read_dir(year_entry.path())? {
+                let day_entry = day_entry?;
+                let Some(day) = day_entry.file_name().to_str().and_then(|s| s.parse().ok()) else { continue };
+                for file_entry in fs::read_dir(day_entry.path())? {
+                    let path = file_entry?.path();
+                    if is_encrypted(&path) {
+                        if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                            inputs.push((year, day, name.to_string()));
+                        }
+                    }
+                }
+            }
+        }
+        inputs.sort();
+        Ok(inputs)
+    }
+}
+
+#[cfg(test)]
+mod inputs_tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-inputs-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let secret = Secret::passphrase("correct horse");
+        let data = encrypt(&secret, b"Time: 7 15 30\n").unwrap();
+        assert!(data.starts_with(MAGIC));
+        assert!(!data.windows(4).any(|window| window == b"Time"));
+        assert_eq!(decrypt(&secret, &data).unwrap(), b"Time: 7 15 30\n");
+
+        // Each encryption is salted afresh
+        assert_ne!(encrypt(&secret, b"Time: 7 15 30\n").unwrap(), data);
+    }
+
+    #[test]
+    fn test_wrong_secret_or_tampering_fails() {
+        let data = encrypt(&Secret::passphrase("correct horse"), b"seeds: 79 14").unwrap();
+        assert!(decrypt(&Secret::passphrase("battery staple"), &data).is_err());
+
+        let mut tampered = data.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&Secret::passphrase("correct horse"), &tampered).is_err());
+        assert!(decrypt(&Secret::passphrase("correct horse"), b"seeds: 79 14").is_err());
+    }
+
+    #[test]
+    fn test_store() {
+        let dir = temp_dir("store");
+        let store = InputStore::new(&dir);
+        let secret = Secret::passphrase("correct horse");
+        store.add(&secret, 2023, 6, DEFAULT_NAME, b"Time: 7\nDistance: 9\n").unwrap();
+        store.add(&secret, 2023, 6, "part2", b"Time: 71530\nDistance: 940200\n").unwrap();
+
+        assert_eq!(store.read(&secret, 2023, 6, "part2").unwrap(), "Time: 71530\nDistance: 940200\n");
+        assert_eq!(store.name_for_part(2023, 6, 1).as_deref(), Some(DEFAULT_NAME));
+        assert_eq!(store.name_for_part(2023, 6, 2).as_deref(), Some("part2"));
+        assert_eq!(store.name_for_part(2023, 5, 2), None);
+        assert_eq!(store.list().unwrap(), vec![(2023, 6, "input".to_string()), (2023, 6, "part2".to_string())]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_refuse_tracked() {
+        let dir = temp_dir("git");
+        let status = Command::new("git").arg("-C").arg(&dir).args(["init", "-q"]).status().unwrap();
+        assert!(status.success());
+        fs::write(dir.join(".gitignore"), "ignored/\n").unwrap();
+        fs::create_dir_all(dir.join("ignored")).unwrap();
+
+        assert_eq!(refuse_tracked(&dir.join("my_input.txt")).unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+        assert!(refuse_tracked(&dir.join("ignored").join("my_input.txt")).is_ok());
+        assert!(refuse_tracked(&dir.join("ignored").join("new").join("my_input.txt")).is_ok());
+        assert!(refuse_tracked(&dir.join("new").join("my_input.txt")).is_err());
+
+        // Outside any work tree nothing is tracked, but without git nothing can be told
+        let outside = env::temp_dir().join(format!("aoc-inputs-outside-{}", std::process::id())).join("my_input.txt");
+        assert_eq!(refuse_tracked_by("git", &outside).map_err(|error| error.to_string()), Ok(()));
+        let error = refuse_tracked_by("aoc-no-such-git", &dir.join("ignored").join("my_input.txt")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        assert!(error.to_string().contains("as git cannot be run"), "{}", error);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::io;
+
+use serde_json::Value;
+
+use crate::all::format_table;
+
+/*
+ * Statistics for a private leaderboard, from the JSON the site exports for it:
+ *
+ * {"event": "2023", "members": {"123": {"id": 123, "name": "Ada", "completion_day_level": {
+ *     "1": {"1": {"get_star_ts": 1701407000, "star_index": 12}, "2": {...}}, ...}}, ...}}
+ *
+ * Times are counted from when each puzzle unlocked, at midnight EST, and the local scores are
+ * worked out again day by day as the site does: for each star, every member gets a point per
+ * member of the board, less one for each member who got the star before them.
+ */
+
+// Puzzles unlock at midnight EST, 5:00 UTC
+const UNLOCK_HOUR_UTC: i64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub id: u64,
+    pub name: String,
+    // When each star was got, by day and part, as (timestamp, star index)
+    pub stars: BTreeMap<(u8, u8), (i64, u64)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    pub year: u16,
+    pub members: Vec<Member>,
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/*
+ * Parses a leaderboard as exported by the site.
+ */
+pub fn parse_board(text: &str) -> io::Result<Board> {
+    let json: Value = serde_json::from_str(text).map_err(|error| invalid(error.to_string()))?;
+    let year = json["event"].as_str()
+        .and_then(|event| event.parse().ok())
+        .ok_or_else(|| invalid("leaderboard has no event year"))?;
+    let members = json["members"].as_object().ok_or_else(|| invalid("leaderboard has no members"))?;
+
+    let mut board = Board { year, members: Vec::new() };
+    for (key, member) in members {
+        let id = member["id"].as_u64().or_else(|| key.parse().ok()).ok_or_else(|| invalid(format!("member {} has no id", key)))?;
+        let name = member["name"].as_str().map_or_else(|| format!("(anonymous user #{})", id), str::to_string);
+        let mut stars = BTreeMap::new();
+        if let Some(days) = member["completion_day_level"].as_object() {
+            for (day, parts) in days {
+                let parts = parts.as_object().ok_or_else(|| invalid(format!("{}: day {} is not an object", name, day)))?;
+                for (part, star) in parts {
+                    let puzzle = day.parse().ok().zip(part.parse().ok());
+                    let timestamp = star["get_star_ts"].as_i64();
+                    let (Some(puzzle), Some(timestamp)) = (puzzle, timestamp) else {
+                        return Err(invalid(format!("{}: bad star for day {} part {}", name, day, part)));
+                    };
+                    stars.insert(puzzle, (timestamp, star["star_index"].as_u64().unwrap_or(0)));
+                }
+            }
+        }
+        board.members.push(Member { id, name, stars });
+    }
+    board.members.sort_by_key(|member| member.id);
+    Ok(board)
+}
+
+/*
+ * Days since the Unix epoch of a date in the proleptic Gregorian calendar.
+ */
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/*
+ * When the puzzle for a day of December unlocked, as a Unix timestamp.
+ */
+pub fn unlock_time(year: u16, day: u8) -> i64 {
+    days_from_civil(year as i64, 12, day as i64) * 86400 + UNLOCK_HOUR_UTC * 3600
+}
+
+/*
+ * One member's results for one day: how long each part took from the unlock, in seconds, how much
+ * longer part 2 took than part 1, and their local score and rank once the day was over.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayStats {
+    pub day: u8,
+    pub name: String,
+    pub id: u64,
+    pub part1: Option<i64>,
+    pub part2: Option<i64>,
+    pub delta: Option<i64>,
+    pub score: u64,
+    pub rank: usize,
+    // Places moved up since the day before (negative if down)
+    pub change: Option<i64>,
+}
+
+/*
+ * The results of every member for every day up to the last day anyone got a star, ordered by day
+ * then rank. Members with the same score share a rank.
+ */
+pub fn stats(board: &Board) -> Vec<DayStats> {
+    let last_day = board.members.iter()
+        .filter_map(|member| member.stars.keys().map(|&(day, _)| day).max())
+        .max()
+        .unwrap_or(0);
+    let mut scores = vec![0u64; board.members.len()];
+    let mut previous_ranks: Option<Vec<usize>> = None;
+    let mut stats = Vec::new();
+
+    for day in 1..=last_day {
+        for part in 1..=2 {
+            let mut finishers: Vec<(usize, (i64, u64))> = board.members.iter()
+                .enumerate()
+                .filter_map(|(i, member)| member.stars.get(&(day, part)).map(|&star| (i, star)))
+                .collect();
+            finishers.sort_by_key(|&(_, star)| star);
+            for (position, (i, _)) in finishers.into_iter().enumerate() {
+                scores[i] += (board.members.len() - position) as u64;
+            }
+        }
+
+        let ranks: Vec<usize> = scores.iter().map(|score| 1 + scores.iter().filter(|other| *other > score).count()).collect();
+        let unlock = unlock_time(board.year, day);
+        let mut day_stats: Vec<DayStats> = board.members.iter()
+            .enumerate()
+            .map(|(i, member)| {
+                let time = |part| member.stars.get(&(day, part)).map(|&(timestamp, _)| (timestamp - unlock).max(0));
+                let (part1, part2) = (time(1), time(2));
+                DayStats {
+                    day,
+                    name: member.name.clone(),
+                    id: member.id,
+                    part1,
+                    part2,
+                    delta: part1.zip(part2).map(|(part1, part2)| part2 - part1),
+                    score: scores[i],
+                    rank: ranks[i],
+                    change: previous_ranks.as_ref().map(|previous| previous[i] as i64 - ranks[i] as i64),
+                }
+            })
+            .collect();
+        day_stats.sort_by(|a, b| a.rank.cmp(&b.rank).then_with(|| a.name.cmp(&b.name)));
+        stats.extend(day_stats);
+        previous_ranks = Some(ranks);
+    }
+    stats
+}
+
+/*
+ * Formats seconds as h:mm:ss.
+ */
+fn format_time(seconds: i64) -> String {
+    format!("{}:{:02}:{:02}", seconds / 3600, seconds % 3600 / 60, seconds % 60)
+}
+
+fn format_change(change: Option<i64>) -> String {
+    match change {
+        None => String::new(),
+        Some(0) => "=".to_string(),
+        Some(change) => format!("{:+}", change),
+    }
+}
+
+pub fn table(stats: &[DayStats]) -> String {
+    let header = ["Day", "Member", "Part 1", "Part 2", "Delta", "Score", "Rank", "Change"].map(str::to_string);
+    let time = |seconds: Option<i64>| seconds.map_or("-".to_string(), format_time);
+    let rows = stats.iter().map(|stats| [
+        stats.day.to_string(),
+        stats.name.clone(),
+        time(stats.part1),
+        time(stats.part2),
+        time(stats.delta),
+        stats.score.to_string(),
+        stats.rank.to_string(),
+        format_change(stats.change),
+    ]);
+    format_table(header, rows)
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/*
+ * The stats as CSV, with times in seconds.
+ */
+pub fn csv(stats: &[DayStats]) -> String {
+    let mut output = "day,member,id,part1_seconds,part2_seconds,delta_seconds,score,rank,rank_change\n".to_string();
+    let number = |value: Option<i64>| value.map_or(String::new(), |value| value.to_string());
+    for stats in stats {
+        let _ = writeln!(
+            output,
+            "{},{},{},{},{},{},{},{},{}",
+            stats.day,
+            csv_field(&stats.name),
+            stats.id,
+            number(stats.part1),
+            number(stats.part2),
+            number(stats.delta),
+            stats.score,
+            stats.rank,
+            number(stats.change),
+        );
+    }
+    output
+}
+
+#[cfg(test)]
+mod leaderboard_tests {
+    use super::*;
+
+    // Day 1 unlocked at 1701406800, day 2 at 1701493200
+    const BOARD: &str = r#"{
+        "event": "2023",
+        "owner_id": 1,
+        "members": {
+            "1": {"id": 1, "name": "Ada", "stars": 3, "local_score": 7, "completion_day_level": {
+                "1": {"1": {"get_star_ts": 1701407100, "star_index": 10}, "2": {"get_star_ts": 1701408000, "star_index": 20}},
+                "2": {"1": {"get_star_ts": 1701500000, "star_index": 50}}
+            }},
+            "2": {"id": 2, "name": null, "stars": 4, "local_score": 11, "completion_day_level": {
+                "1": {"1": {"get_star_ts": 1701407000, "star_index": 5}, "2": {"get_star_ts": 1701410000, "star_index": 30}},
+                "2": {"1": {"get_star_ts": 1701493500, "star_index": 40}, "2": {"get_star_ts": 1701494000, "star_index": 45}}
+            }},
+            "3": {"id": 3, "name": "Grace, H.", "stars": 1, "local_score": 1, "completion_day_level": {
+                "2": {"1": {"get_star_ts": 1701510000, "star_index": 60}}
+            }}
+        }
+    }"#;
+
+    #[test]
+    fn test_unlock_time() {
+        assert_eq!(unlock_time(2023, 1), 1701406800);
+        assert_eq!(unlock_time(2023, 25), 1703480400);
+        assert_eq!(unlock_time(2015, 1), 1448946000);
+    }
+
+    #[test]
+    fn test_parse_board() {
+        let board = parse_board(BOARD).unwrap();
+        assert_eq!(board.year, 2023);
+        let names: Vec<&str> = board.members.iter().map(|member| member.name.as_str()).collect();
+        assert_eq!(names, vec!["Ada", "(anonymous user #2)", "Grace, H."]);
+        assert_eq!(board.members[0].stars[&(1, 2)], (1701408000, 20));
+
+        assert!(parse_board("{\"members\": {}}").unwrap_err().to_string().contains("no event year"));
+        assert!(parse_board("{\"event\": \"2023\", \"members\": {\"1\": {\"completion_day_level\": {\"x\": {\"1\": {\"get_star_ts\": 1}}}}}}").is_err());
+    }
+
+    #[test]
+    fn test_stats() {
+        let board = parse_board(BOARD).unwrap();
+        let stats = stats(&board);
+        // Day, member id, part 1 time, delta, score, rank and change
+        type Row = (u8, u64, Option<i64>, Option<i64>, u64, usize, Option<i64>);
+        let summary: Vec<Row> = stats.iter()
+            .map(|stats| (stats.day, stats.id, stats.part1, stats.delta, stats.score, stats.rank, stats.change))
+            .collect();
+        assert_eq!(summary, vec![
+            (1, 2, Some(200), Some(3000), 5, 1, None),
+            (1, 1, Some(300), Some(900), 5, 1, None),
+            (1, 3, None, None, 0, 3, None),
+            (2, 2, Some(300), Some(500), 11, 1, Some(0)),
+            (2, 1, Some(6800), None, 7, 2, Some(-1)),
+            (2, 3, Some(16800), None, 1, 3, Some(0)),
+        ]);
+    }
+
+    #[test]
+    fn test_scores_match_the_site() {
+        let board = parse_board(BOARD).unwrap();
+        let json: Value = serde_json::from_str(BOARD).unwrap();
+        for stats in stats(&board).iter().filter(|stats| stats.day == 2) {
+            let local_score = json["members"][stats.id.to_string()]["local_score"].as_u64().unwrap();
+            assert_eq!(stats.score, local_score, "{}", stats.name);
+        }
+    }
+
+    #[test]
+    fn test_table_and_csv() {
+        let stats = stats(&parse_board(BOARD).unwrap());
+        let table = table(&stats);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "Day  Member               Part 1   Part 2   Delta    Score  Rank  Change");
+        assert_eq!(lines[4], "2    (anonymous user #2)  0:05:00  0:13:20  0:08:20  11     1     =");
+        assert_eq!(lines[5], "2    Ada                  1:53:20  -        -        7      2     -1");
+
+        let csv = csv(&stats);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "day,member,id,part1_seconds,part2_seconds,delta_seconds,score,rank,rank_change");
+        assert_eq!(lines[3], "1,\"Grace, H.\",3,,,,0,3,");
+        assert_eq!(lines[6], "2,\"Grace, H.\",3,16800,,,1,3,0");
+    }
+}
@@ -0,0 +1,456 @@
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+mod all;
+mod crosscheck;
+mod history;
+mod inputs;
+mod leaderboard;
+mod plugins;
+mod registry;
+mod serve;
+mod status;
+
+use common::budget::{self, Budget, CountingAllocator};
+use common::solver::Solver;
+use history::{Answers, HistoryEntry};
+use inputs::{InputStore, Secret};
+use registry::Registry;
+
+const USAGE: &str = "\
+usage: aoc list
+       aoc check YEAR DAY FILE [--key-file PATH]
+       aoc run YEAR DAY PART [FILE] [--name SOLVER] [--input NAME] [--key-file PATH]
+               [--timeout DURATION] [--max-mem SIZE]
+       aoc repl YEAR DAY [FILE] [--input NAME] [--key-file PATH]
+       aoc all [--year YEAR] [--timeout DURATION] [--record] [--key-file PATH]
+       aoc crosscheck [--year YEAR] [--day DAY] [--count N] [--seed SEED] [--size SIZE]
+       aoc status [--year YEAR] [--json]
+       aoc leaderboard stats FILE [--csv]
+       aoc serve [--address HOST:PORT]
+       aoc inputs add YEAR DAY FILE [--name NAME] [--remove] [--key-file PATH]
+       aoc inputs list
+       aoc inputs cat YEAR DAY [--name NAME] [--key-file PATH]
+       aoc inputs export YEAR DAY OUTPUT [--name NAME] [--key-file PATH]";
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8023";
+
+// Options that take a value, so that the value is not mistaken for a positional argument
+const VALUE_OPTIONS: [&str; 11] = [
+    "--address", "--name", "--input", "--key-file", "--timeout", "--max-mem", "--year", "--day", "--count", "--seed",
+    "--size",
+];
+
+// How long past its time limit a solver is given to reach a cancellation point, before the runner
+// gives up on it
+const GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn usage_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, USAGE)
+}
+
+/*
+ * Returns the value following an option such as --address, if the option is present.
+ */
+fn option_value<'a>(args: &'a [String], option: &str) -> io::Result<Option<&'a str>> {
+    match args.iter().position(|arg| arg == option) {
+        Some(i) => args.get(i + 1).map(|value| Some(value.as_str())).ok_or_else(usage_error),
+        None => Ok(None),
+    }
+}
+
+/*
+ * Returns the arguments that are neither options nor the values of options.
+ */
+fn positional_args(args: &[String]) -> Vec<&str> {
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if VALUE_OPTIONS.contains(&arg.as_str()) {
+            iter.next();
+        } else if !arg.starts_with("--") {
+            positional.push(arg.as_str());
+        }
+    }
+    positional
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> io::Result<T> {
+    value.parse().map_err(|_| usage_error())
+}
+
+/*
+ * Reads the budget for a run from --timeout and --max-mem.
+ */
+fn parse_budget(args: &[String]) -> io::Result<Budget> {
+    let timeout = option_value(args, "--timeout")?
+        .map(|value| budget::parse_duration(value).ok_or_else(usage_error))
+        .transpose()?;
+    let max_memory = option_value(args, "--max-mem")?
+        .map(|value| budget::parse_size(value).ok_or_else(usage_error))
+        .transpose()?;
+    Ok(Budget { timeout, max_memory })
+}
+
+/*
+ * Prints every violation of the day's grammar by the input, and fails if there are any.
+ */
+fn check_input(registry: &Registry, year: u16, day: u8, input: &str, source: &str) -> io::Result<()> {
+    let Some(grammar) = registry.grammar(year, day) else {
+        return Ok(());
+    };
+    let violations = grammar.validate(input);
+    for violation in &violations {
+        eprintln!("{}: {}", source, violation);
+    }
+    match violations.len() {
+        0 => Ok(()),
+        count => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {} violation(s) of the grammar for {}/{}", source, count, year, day),
+        )),
+    }
+}
+
+fn check(registry: &Registry, args: &[String]) -> io::Result<()> {
+    let [year, day, file] = positional_args(args)[..] else {
+        return Err(usage_error());
+    };
+    let (year, day) = (parse(year)?, parse(day)?);
+    if registry.grammar(year, day).is_none() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("no grammar for {}/{}", year, day)));
+    }
+    let input = inputs::read_input(Path::new(file), option_value(args, "--key-file")?)?;
+    check_input(registry, year, day, &input, file)?;
+    println!("{}: ok", file);
+    Ok(())
+}
+
+/*
+ * Reads the input file given, or otherwise the input named by --input or stored for the part,
+ * along with where it came from.
+ */
+fn load_input(args: &[String], year: u16, day: u8, part: u8, file: Option<&str>) -> io::Result<(String, String)> {
+    let key_file = option_value(args, "--key-file")?;
+    match file {
+        Some(file) => Ok((inputs::read_input(Path::new(file), key_file)?, file.to_string())),
+        None => {
+            let store = InputStore::new(inputs::default_dir());
+            let name = match option_value(args, "--input")? {
+                Some(name) => name.to_string(),
+                None => store.name_for_part(year, day, part).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, format!("no input stored for {}/{}", year, day))
+                })?,
+            };
+            let source = format!("stored input {}/{}/{}", year, day, name);
+            Ok((store.read(&Secret::find(key_file)?, year, day, &name)?, source))
+        }
+    }
+}
+
+/*
+ * Solves one part, for the input file given or otherwise the input stored for it. The input is
+ * checked against the day's grammar first, and not solved if it does not follow it.
+ *
+ * The solver is cancelled if it exceeds the time or memory budget given, and reports the progress
+ * it had made. A solver that fails to stop within the grace period ends the process instead.
+ */
+fn run(registry: &Registry, args: &[String]) -> io::Result<()> {
+    let positional = positional_args(args);
+    let (year, day, part, file) = match positional.as_slice() {
+        [year, day, part] => (parse(year)?, parse(day)?, parse(part)?, None),
+        [year, day, part, file] => (parse(year)?, parse(day)?, parse(part)?, Some(*file)),
+        _ => return Err(usage_error()),
+    };
+    let budget = parse_budget(args)?;
+
+    let solver = registry.find(year, day, part, option_value(args, "--name")?).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, format!("no solver for {}/{}/{}", year, day, part))
+    })?;
+    let solver_name = solver.info().to_string();
+    let (input, source) = load_input(args, year, day, part, file)?;
+
+    if let Some(timeout) = budget.timeout {
+        thread::spawn(move || {
+            thread::sleep(timeout + GRACE_PERIOD);
+            eprintln!("{} did not stop within {:?} of its time limit", solver_name, GRACE_PERIOD);
+            process::exit(124);
+        });
+    }
+    let guard = budget.install();
+    check_input(registry, year, day, &input, &source)?;
+    let result = solver.solve(&input);
+    drop(guard);
+
+    let solution = result?;
+    solution.print_diagnostics();
+    println!("Answer: {}", solution.answer);
+    Ok(())
+}
+
+/*
+ * Parses a day's input once, from the file given or otherwise the input stored for part 1, then
+ * reads commands exploring it from stdin until it ends or the user quits.
+ */
+fn repl(registry: &Registry, args: &[String]) -> io::Result<()> {
+    let positional = positional_args(args);
+    let (year, day, file) = match positional.as_slice() {
+        [year, day] => (parse(year)?, parse(day)?, None),
+        [year, day, file] => (parse(year)?, parse(day)?, Some(*file)),
+        _ => return Err(usage_error()),
+    };
+    let explore = registry.explorer(year, day).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, format!("no explorer for {}/{}", year, day))
+    })?;
+    let (input, source) = load_input(args, year, day, 1, file)?;
+    check_input(registry, year, day, &input, &source)?;
+
+    let mut explorer = explore(&input)?;
+    let stdin = io::stdin();
+    let prompt = stdin.is_terminal();
+    if prompt {
+        println!("Exploring {}, type help for commands", source);
+    }
+    common::repl::run(explorer.as_mut(), stdin.lock(), io::stdout().lock(), prompt)
+}
+
+/*
+ * Runs every registered solver on its stored input, in parallel, and prints a summary. Fails if
+ * any solver fails or gives an answer other than the one recorded for its part.
+ *
+ * With --record, answers for parts that have none recorded yet are recorded.
+ */
+fn run_all(registry: &Registry, args: &[String]) -> io::Result<()> {
+    if !positional_args(args).is_empty() || option_value(args, "--max-mem")?.is_some() {
+        return Err(usage_error());
+    }
+    let year = option_value(args, "--year")?.map(parse).transpose()?;
+    let budget = parse_budget(args)?;
+    let store = InputStore::new(inputs::default_dir());
+    // Only needed if there are inputs to decrypt
+    let secret = Secret::find(option_value(args, "--key-file")?).map_err(|error| error.to_string());
+
+    let answers_path = history::answers_path(&inputs::default_dir());
+    let mut answers = Answers::load(&answers_path)?;
+    let outcomes = all::run_all(registry, year, |info| {
+        let Some(name) = store.name_for_part(info.year, info.day, info.part) else {
+            return Ok(None);
+        };
+        let secret = secret.as_ref().map_err(String::clone)?;
+        store.read(secret, info.year, info.day, &name).map(Some).map_err(|error| error.to_string())
+    }, &answers, &budget);
+
+    print!("{}", all::summary_table(&outcomes));
+
+    let time = HistoryEntry::now();
+    let entries: Vec<HistoryEntry> = outcomes.iter().filter_map(|outcome| outcome.history_entry(time)).collect();
+    let history_path = history::history_path(&inputs::default_dir());
+    inputs::refuse_tracked(&history_path)?;
+    history::append_history(&history_path, &entries)?;
+
+    if args.iter().any(|arg| arg == "--record") {
+        let new: Vec<&all::Outcome> = outcomes.iter().filter(|outcome| outcome.status == all::Status::New).collect();
+        for outcome in &new {
+            if let Some(answer) = &outcome.answer {
+                answers.insert(outcome.info.year, outcome.info.day, outcome.info.part, answer);
+            }
+        }
+        if !new.is_empty() {
+            // Unlike the runs, the answers are committed along with the inputs they are for
+            answers.save(&answers_path)?;
+            println!("Recorded {} new answer(s) in {}", new.len(), answers_path.display());
+        }
+    }
+
+    let failures = outcomes.iter().filter(|outcome| outcome.status.is_failure()).count();
+    match failures {
+        0 => Ok(()),
+        _ => Err(io::Error::other(format!("{} of {} solvers failed", failures, outcomes.len()))),
+    }
+}
+
+/*
+ * Crosschecks the solvers of each part on generated inputs, printing a line per part and the
+ * minimised input for any disagreement. Fails if the solvers of any part disagree.
+ */
+fn crosscheck_command(registry: &Registry, args: &[String]) -> io::Result<()> {
+    let year = option_value(args, "--year")?.map(parse).transpose()?;
+    let day = option_value(args, "--day")?.map(parse).transpose()?;
+    let count = option_value(args, "--count")?.map(parse).transpose()?.unwrap_or(100);
+    let seed = option_value(args, "--seed")?.map(parse).transpose()?.unwrap_or(2023);
+    let size = option_value(args, "--size")?.map(parse).transpose()?.unwrap_or(10);
+
+    let reports = crosscheck::crosscheck(registry, year, day, count, seed, size);
+    let mut disagreements = 0;
+    for report in &reports {
+        let part = format!("{}/{}/{}  {}", report.year, report.day, report.part, report.solvers.join(", "));
+        let Some(disagreement) = &report.disagreement else {
+            println!("{}: agree on {} inputs", part, report.inputs);
+            continue;
+        };
+        disagreements += 1;
+        println!(
+            "{}: disagree on input {} (seed {}, size {}), minimised to:",
+            part, report.inputs, disagreement.seed, disagreement.size,
+        );
+        print!("{}", disagreement.input);
+        for (name, answer) in &disagreement.answers {
+            match answer {
+                Ok(answer) => println!("    {}: {}", name, answer),
+                Err(error) => println!("    {}: error: {}", name, error),
+            }
+        }
+    }
+    match disagreements {
+        0 => Ok(()),
+        _ => Err(io::Error::other(format!("solvers disagree on {} of {} parts", disagreements, reports.len()))),
+    }
+}
+
+/*
+ * Shows the progress on every part: as a calendar for each year, or with --json as a list of
+ * parts.
+ */
+fn status_command(registry: &Registry, args: &[String]) -> io::Result<()> {
+    if !positional_args(args).is_empty() {
+        return Err(usage_error());
+    }
+    let year = option_value(args, "--year")?.map(parse).transpose()?;
+    let answers = Answers::load(&history::answers_path(&inputs::default_dir()))?;
+    let history = history::load_history(&history::history_path(&inputs::default_dir()))?;
+    let statuses = status::statuses(registry, &answers, &history, year);
+
+    if args.iter().any(|arg| arg == "--json") {
+        println!("{}", serde_json::to_string_pretty(&status::to_json(&statuses))?);
+    } else {
+        print!("{}", status::calendar(&statuses));
+    }
+    Ok(())
+}
+
+/*
+ * Prints statistics for a private leaderboard exported from the site, as a table or CSV.
+ */
+fn leaderboard_command(args: &[String]) -> io::Result<()> {
+    let ["stats", file] = positional_args(args)[..] else {
+        return Err(usage_error());
+    };
+    let board = leaderboard::parse_board(&fs::read_to_string(file)?)
+        .map_err(|error| io::Error::new(error.kind(), format!("{}: {}", file, error)))?;
+    let stats = leaderboard::stats(&board);
+    if args.iter().any(|arg| arg == "--csv") {
+        print!("{}", leaderboard::csv(&stats));
+    } else {
+        print!("{}", leaderboard::table(&stats));
+    }
+    Ok(())
+}
+
+fn inputs_command(args: &[String]) -> io::Result<()> {
+    let store = InputStore::new(inputs::default_dir());
+    let name = option_value(args, "--name")?.unwrap_or(inputs::DEFAULT_NAME);
+    let key_file = option_value(args, "--key-file")?;
+
+    match positional_args(args).as_slice() {
+        ["add", year, day, file] => {
+            let plaintext = fs::read(file)?;
+            let path = store.add(&Secret::find(key_file)?, parse(year)?, parse(day)?, name, &plaintext)?;
+            println!("Stored {} as {}", file, path.display());
+            if args.iter().any(|arg| arg == "--remove") {
+                fs::remove_file(file)?;
+                println!("Removed {}", file);
+            }
+            Ok(())
+        }
+        ["list"] => {
+            for (year, day, name) in store.list()? {
+                println!("{}/{}/{}", year, day, name);
+            }
+            Ok(())
+        }
+        ["cat", year, day] => {
+            let input = store.read(&Secret::find(key_file)?, parse(year)?, parse(day)?, name)?;
+            io::stdout().write_all(input.as_bytes())
+        }
+        ["export", year, day, output] => {
+            let output = Path::new(output);
+            inputs::refuse_tracked(output)?;
+            let input = store.read(&Secret::find(key_file)?, parse(year)?, parse(day)?, name)?;
+            if let Some(parent) = output.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(output, input)
+        }
+        _ => Err(usage_error()),
+    }
+}
+
+/*
+ * Registers the solvers of the plugins in the plugins directory after the built-in ones. A plugin
+ * that cannot be loaded, or a solver with the same part and name as one already registered, is
+ * reported and left out, and so are all of them if the directory cannot be read.
+ */
+fn register_plugins(registry: &mut Registry) {
+    let dir = plugins::default_dir();
+    let (solvers, failures) = match plugins::load_dir(&dir) {
+        Ok(loaded) => loaded,
+        Err(error) => {
+            eprintln!("plugins in {} not loaded: {}", dir.display(), error);
+            return;
+        }
+    };
+    for failure in failures {
+        eprintln!("plugin {}", failure);
+    }
+    for solver in solvers {
+        let info = solver.info();
+        if registry.find(info.year, info.day, info.part, Some(&info.name)).is_some() {
+            eprintln!("plugin solver {} is already registered", info);
+        } else {
+            registry.register(solver);
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().collect();
+    let mut registry = Registry::builtin();
+    // Loading a plugin runs its code, so only for the commands that run or list solvers
+    if matches!(args.get(1).map(String::as_str), Some("list" | "run" | "all" | "serve" | "crosscheck")) {
+        register_plugins(&mut registry);
+    }
+
+    match args.get(1).map(String::as_str) {
+        Some("list") => {
+            for solver in registry.solvers() {
+                println!("{}", solver.info());
+            }
+            Ok(())
+        }
+        Some("check") => check(&registry, &args[2..]),
+        Some("run") => run(&registry, &args[2..]),
+        Some("repl") => repl(&registry, &args[2..]),
+        Some("all") => run_all(&registry, &args[2..]),
+        Some("crosscheck") => crosscheck_command(&registry, &args[2..]),
+        Some("status") => status_command(&registry, &args[2..]),
+        Some("leaderboard") => leaderboard_command(&args[2..]),
+        Some("serve") => {
+            let address = option_value(&args[2..], "--address")?.unwrap_or(DEFAULT_ADDRESS);
+            let listener = TcpListener::bind(address)?;
+            println!("Serving on http://{}", listener.local_addr()?);
+            serve::serve(listener, Arc::new(registry))
+        }
+        Some("inputs") => inputs_command(&args[2..]),
+        _ => Err(usage_error()),
+    }
+}
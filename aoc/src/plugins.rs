@@ -0,0 +1,102 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use common::plugin::{self, ForeignSolver, Manifest};
+use common::solver::{Solution, SolveError, Solver, SolverInfo};
+use libloading::Library;
+
+/*
+ * Solvers loaded from the shared libraries in the plugins directory, which implement
+ * common::plugin. They are registered after the built-in solvers, to be listed, run and timed
+ * like them.
+ */
+
+pub fn default_dir() -> PathBuf {
+    env::var_os("AOC_PLUGINS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("plugins"))
+}
+
+/*
+ * A plugin's solver, which keeps the library it came from loaded.
+ */
+pub struct PluginSolver {
+    solver: ForeignSolver,
+    _library: Arc<Library>,
+}
+
+impl Solver for PluginSolver {
+    fn info(&self) -> &SolverInfo {
+        self.solver.info()
+    }
+
+    fn solve(&self, input: &str) -> Result<Solution, SolveError> {
+        self.solver.solve(input)
+    }
+}
+
+/*
+ * Loads the solvers of one plugin. Loading a library runs its initialisation code, so only
+ * libraries that are trusted belong in the plugins directory.
+ */
+pub fn load(path: &Path) -> Result<Vec<PluginSolver>, String> {
+    // SAFETY: plugins are trusted, as above
+    let library = Arc::new(unsafe { Library::new(path) }.map_err(|error| error.to_string())?);
+    let manifest = unsafe { library.get::<extern "C" fn() -> *const Manifest>(plugin::ENTRY_POINT.as_bytes()) }
+        .map_err(|_| format!("no {} function", plugin::ENTRY_POINT))?();
+    // SAFETY: a library exporting the entry point follows the ABI, and each solver keeps it loaded
+    let solvers = unsafe { plugin::foreign_solvers(&*manifest) }?;
+    Ok(solvers.into_iter().map(|solver| PluginSolver { solver, _library: Arc::clone(&library) }).collect())
+}
+
+/*
+ * Loads the solvers of every shared library in the directory, with why any library could not be
+ * loaded. A missing directory has no plugins.
+ */
+pub fn load_dir(dir: &Path) -> io::Result<(Vec<PluginSolver>, Vec<String>)> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), Vec::new())),
+        Err(error) => return Err(error),
+    };
+    let mut paths = entries
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+    paths.retain(|path| path.extension().is_some_and(|extension| extension == env::consts::DLL_EXTENSION));
+    paths.sort();
+
+    let mut solvers = Vec::new();
+    let mut failures = Vec::new();
+    for path in paths {
+        match load(&path) {
+            Ok(loaded) => solvers.extend(loaded),
+            Err(message) => failures.push(format!("{}: {}", path.display(), message)),
+        }
+    }
+    Ok((solvers, failures))
+}
+
+#[cfg(test)]
+mod plugins_tests {
+    use super::*;
+
+    #[test]
+    fn test_load_dir() {
+        let dir = env::temp_dir().join(format!("aoc_plugins_test_{}", std::process::id()));
+        assert!(load_dir(&dir).unwrap().0.is_empty());
+
+        fs::create_dir_all(&dir).unwrap();
+        let library = dir.join(format!("not_a_library.{}", env::consts::DLL_EXTENSION));
+        fs::write(&library, "not a library").unwrap();
+        fs::write(dir.join("README.txt"), "ignored").unwrap();
+        let (solvers, failures) = load_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(solvers.is_empty());
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with(&format!("{}: ", library.display())), "{}", failures[0]);
+    }
+}
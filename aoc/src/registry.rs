@@ -0,0 +1,230 @@
+use common::frames::Recording;
+use common::grammar::Grammar;
+use common::input::lines;
+use common::random::Rng;
+use common::repl::Explorer;
+use common::solver::{FnSolver, SolveError, Solver, SolverInfo};
+
+/*
+ * Parses a day's input once, for exploring it with commands.
+ */
+pub type Explore = fn(&str) -> Result<Box<dyn Explorer>, SolveError>;
+
+/*
+ * Generates a random input for a day, of roughly the size given, for crosschecking its solvers.
+ */
+pub type Generate = fn(&mut Rng, usize) -> String;
+
+/*
+ * All solvers the runner knows about, in the order they were registered.
+ */
+pub struct Registry {
+    solvers: Vec<Box<dyn Solver>>,
+    grammars: Vec<(u16, u8, Grammar)>,
+    explorers: Vec<(u16, u8, Explore)>,
+    generators: Vec<(u16, u8, Generate)>,
+    references: Vec<SolverInfo>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry { solvers: Vec::new(), grammars: Vec::new(), explorers: Vec::new(), generators: Vec::new(), references: Vec::new() }
+    }
+
+    /*
+     * Registry of every day's solvers built into the runner.
+     *
+     * Day 5 part 1 was never written separately, and day 6 part 2 expects the numbers in the
+     * input to have been concatenated by hand.
+     *
+     * Days 5 and 6 have brute-force references and input generators, to crosscheck their solvers.
+     */
+    pub fn builtin() -> Self {
+        let mut registry = Registry::new();
+        registry.register(FnSolver::new(2023, 1, 1, "sum_calibration_values", |input| {
+            day1::sum_calibration_document(input.as_bytes(), &day1::Mode::part(1), day1::line_summary::LineCheck::Off)
+        }));
+        registry.register(FnSolver::new(2023, 1, 2, "sum_calibration_values", |input| {
+            day1::sum_calibration_document(input.as_bytes(), &day1::Mode::part(2), day1::line_summary::LineCheck::Off)
+        }));
+        registry.register(FnSolver::new(2023, 2, 1, "sum_games", |input| day2::sum_games_lines(lines(input.as_bytes()))));
+        registry.register(FnSolver::new(2023, 2, 2, "sum_powers", |input| day2::sum_powers_lines(lines(input.as_bytes()))));
+        registry.register(FnSolver::new(2023, 3, 1, "sum_parts", |input| {
+            day3::parts::sum_parts(input.as_bytes(), &mut Recording::disabled())
+        }));
+        registry.register(FnSolver::new(2023, 3, 2, "sum_gears", |input| day3::gears::sum_gears(input.as_bytes())));
+        registry.register(FnSolver::new(2023, 4, 1, "sum_points", |input| day4::sum_points_lines(lines(input.as_bytes()))));
+        registry.register(FnSolver::new(2023, 4, 2, "sum_cards", |input| day4::sum_cards_lines(lines(input.as_bytes()))));
+        registry.register(FnSolver::new(2023, 5, 2, "map_seeds", |input| day5::map_seeds(input, &mut Recording::disabled())));
+        registry.register(FnSolver::new(2023, 6, 1, "product_of_ways_count", |input| {
+            day6::product_of_ways_count(input.as_bytes())
+        }));
+        registry.register(FnSolver::new(2023, 6, 2, "product_of_ways_count", |input| {
+            day6::product_of_ways_count(input.as_bytes())
+        }));
+
+        registry.register_grammar(2023, 1, day1::GRAMMAR);
+        registry.register_grammar(2023, 2, day2::GRAMMAR);
+        registry.register_grammar(2023, 3, day3::GRAMMAR);
+        registry.register_grammar(2023, 4, day4::GRAMMAR);
+        registry.register_grammar(2023, 5, day5::GRAMMAR);
+        registry.register_grammar(2023, 6, day6::GRAMMAR);
+
+        registry.register_explorer(2023, 2, day2::explore::explorer);
+        registry.register_explorer(2023, 3, day3::explore::explorer);
+        registry.register_explorer(2023, 4, day4::explore::explorer);
+        registry.register_explorer(2023, 5, day5::explore::explorer);
+        registry.register_explorer(2023, 6, day6::explore::explorer);
+
+        registry.register_reference(FnSolver::new(2023, 5, 2, "map_seeds_by_trial", day5::map_seeds_by_trial));
+        registry.register_reference(FnSolver::new(2023, 6, 1, "product_of_ways_by_trial", |input| {
+            day6::product_of_ways_by_trial(input.as_bytes())
+        }));
+        registry.register_reference(FnSolver::new(2023, 6, 2, "product_of_ways_by_trial", |input| {
+            day6::product_of_ways_by_trial(input.as_bytes())
+        }));
+        registry.register_generator(2023, 5, day5::generate_almanac);
+        registry.register_generator(2023, 6, day6::generate_races);
+        registry
+    }
+
+    pub fn register(&mut self, solver: impl Solver + 'static) {
+        self.solvers.push(Box::new(solver));
+    }
+
+    /*
+     * Declares the grammar that a day's input must follow.
+     */
+    pub fn register_grammar(&mut self, year: u16, day: u8, grammar: Grammar) {
+        self.grammars.push((year, day, grammar));
+    }
+
+    pub fn grammar(&self, year: u16, day: u8) -> Option<&Grammar> {
+        self.grammars.iter().find(|&&(y, d, _)| y == year && d == day).map(|(_, _, grammar)| grammar)
+    }
+
+    /*
+     * Declares how to explore a day's input, for the repl.
+     */
+    pub fn register_explorer(&mut self, year: u16, day: u8, explore: Explore) {
+        self.explorers.push((year, day, explore));
+    }
+
+    pub fn explorer(&self, year: u16, day: u8) -> Option<Explore> {
+        self.explorers.iter().find(|&&(y, d, _)| y == year && d == day).map(|&(_, _, explore)| explore)
+    }
+
+    /*
+     * Registers a solver that is simple enough to trust but too slow for real inputs, to check
+     * the other solvers of its part against. It is left out of runs of every solver.
+     */
+    pub fn register_reference(&mut self, solver: impl Solver + 'static) {
+        self.references.push(solver.info().clone());
+        self.register(solver);
+    }
+
+    pub fn is_reference(&self, info: &SolverInfo) -> bool {
+        self.references.contains(info)
+    }
+
+    /*
+     * Declares how to generate inputs for a day, for crosschecking its solvers.
+     */
+    pub fn register_generator(&mut self, year: u16, day: u8, generate: Generate) {
+        self.generators.push((year, day, generate));
+    }
+
+    pub fn generators(&self) -> impl Iterator<Item = (u16, u8, Generate)> + '_ {
+        self.generators.iter().copied()
+    }
+
+    pub fn solvers(&self) -> impl Iterator<Item = &dyn Solver> {
+        self.solvers.iter().map(|solver| solver.as_ref())
+    }
+
+    /*
+     * Finds the solver for a part, by name if given, otherwise the first one registered.
+     */
+    pub fn find(&self, year: u16, day: u8, part: u8, name: Option<&str>) -> Option<&dyn Solver> {
+        self.solvers().find(|solver| {
+            let info = solver.info();
+            info.year == year && info.day == day && info.part == part && name.is_none_or(|name| info.name == name)
+        })
+    }
+}
+
+#[cfg(test)]
+mod registry_tests {
+    use super::*;
+    use common::budget::Budget;
+    use std::time::Duration;
+
+    #[test]
+    fn test_grammars_accept_examples() {
+        let registry = Registry::builtin();
+        let examples = [
+            (1, include_str!("../../2023/1/example_calibration_document_2.txt")),
+            (1, include_str!("../../2023/1/example_calibration_document_unicode.txt")),
+            (2, include_str!("../../2023/2/example_record.txt")),
+            (3, include_str!("../../2023/3/example_schematic.txt")),
+            (4, include_str!("../../2023/4/example_cards.txt")),
+            (5, include_str!("../../2023/5/example_almanac.txt")),
+            (6, "Time:      7  15   30\nDistance:  9  40  200\n"),
+        ];
+        for (day, input) in examples {
+            assert_eq!(registry.grammar(2023, day).unwrap().validate(input), vec![], "day {}", day);
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs_follow_grammars() {
+        let registry = Registry::builtin();
+        for (year, day, generate) in registry.generators() {
+            for seed in 0..20 {
+                let input = generate(&mut Rng::new(seed), 1 + seed as usize);
+                assert_eq!(registry.grammar(year, day).unwrap().validate(&input), vec![], "{}/{} seed {}:\n{}", year, day, seed, input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_budget_cancels_solvers() {
+        let registry = Registry::builtin();
+        let solver = registry.find(2023, 5, 2, None).unwrap();
+        let input = include_str!("../../2023/5/example_almanac.txt");
+
+        let guard = Budget { timeout: Some(Duration::ZERO), max_memory: None }.install();
+        let error = solver.solve(input).unwrap_err();
+        drop(guard);
+        assert!(error.to_string().starts_with("cancelled: time limit of 0s exceeded (while reading the "), "{}", error);
+        assert_eq!(solver.solve(input).unwrap().answer, "46");
+    }
+
+    #[test]
+    fn test_grammars_reject_malformed_inputs() {
+        let registry = Registry::builtin();
+        let messages = |day, input| -> Vec<String> {
+            registry.grammar(2023, day).unwrap().validate(input).iter().map(ToString::to_string).collect()
+        };
+        assert_eq!(messages(1, "1abc2\nx\ty\n"), vec!["line 2, column 2: expected printable character or end of line, found '\t'"]);
+        assert_eq!(messages(2, "Game 1: 3 blue, 4 red
+Game 2 1 blue
+Game 3: 2 purple
+"), vec![
+            "line 2, column 7: expected ': ', found ' '",
+            "line 3, column 11: expected 'red', 'green' or 'blue', found 'p'",
+        ]);
+        assert_eq!(messages(4, "Card 1: 41 48 | 83 86
+Card 2: 13 x2 | 61
+"), vec![
+            "line 2, column 12: expected number or '|', found 'x'",
+        ]);
+        assert_eq!(messages(5, "seeds: 79 14
+
+seed-to-soil map:
+50 98
+"), vec![
+            "line 4, column 6: expected space, found end of line",
+        ]);
+    }
+}
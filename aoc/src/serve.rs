@@ -0,0 +1,271 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+use serde_json::{json, Value};
+
+use crate::registry::Registry;
+
+/*
+ * Local HTTP service exposing every registered solver.
+ *
+ * GET  /solvers                  lists the registered solvers
+ * POST /YEAR/DAY/PART[?name=N]   solves the part for the input text in the request body
+ *
+ * Responses are JSON. A solve responds with the answer, the time taken to solve in milliseconds
+ * and the solver's diagnostics (its trace lines), or with an error. Each connection handles one
+ * request.
+ */
+
+// Refuse request bodies larger than this
+const MAX_BODY_LENGTH: usize = 64 * 1024 * 1024;
+
+struct Request {
+    method: String,
+    path: String,
+    query: Option<String>,
+    body: Vec<u8>,
+}
+
+pub fn serve(listener: TcpListener, registry: Arc<Registry>) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let registry = Arc::clone(&registry);
+        thread::spawn(move || {
+            if let Err(error) = handle_connection(stream, &registry) {
+                eprintln!("connection failed: {}", error);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream, registry: &Registry) -> io::Result<()> {
+    let (status, body) = match read_request(&mut BufReader::new(&mut stream)) {
+        Ok(request) => route(&request, registry),
+        Err(error) if error.kind() == io::ErrorKind::InvalidData => (400, json!({ "error": error.to_string() })),
+        Err(error) => return Err(error),
+    };
+    write_response(&mut stream, status, &body)
+}
+
+fn read_request(reader: &mut impl BufRead) -> io::Result<Request> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(invalid("malformed request line"));
+    };
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path, Some(query.to_string())),
+        None => (target, None),
+    };
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Err(invalid("connection closed in headers"));
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().map_err(|_| invalid("invalid Content-Length"))?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_LENGTH {
+        return Err(invalid("request body too large"));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Request { method: method.to_string(), path: path.to_string(), query, body })
+}
+
+fn route(request: &Request, registry: &Registry) -> (u16, Value) {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["solvers"]) => (200, list_solvers(registry)),
+        ("POST", [year, day, part]) => {
+            match (year.parse(), day.parse(), part.parse()) {
+                (Ok(year), Ok(day), Ok(part)) => solve(registry, year, day, part, request),
+                _ => (404, json!({ "error": format!("no such solver: {}", request.path) })),
+            }
+        }
+        (_, ["solvers"]) | (_, [_, _, _]) => (405, json!({ "error": format!("{} not allowed here", request.method) })),
+        _ => (404, json!({ "error": format!("not found: {}", request.path) })),
+    }
+}
+
+fn list_solvers(registry: &Registry) -> Value {
+    let solvers: Vec<Value> = registry.solvers()
+        .map(|solver| {
+            let info = solver.info();
+            json!({ "year": info.year, "day": info.day, "part": info.part, "name": info.name })
+        })
+        .collect();
+    json!({ "solvers": solvers })
+}
+
+fn solve(registry: &Registry, year: u16, day: u8, part: u8, request: &Request) -> (u16, Value) {
+    let name = request.query.as_deref().and_then(|query| {
+        query.split('&').find_map(|pair| pair.strip_prefix("name="))
+    });
+    let Some(solver) = registry.find(year, day, part, name) else {
+        return (404, json!({ "error": format!("no such solver: {}", request.path) }));
+    };
+    let Ok(input) = std::str::from_utf8(&request.body) else {
+        return (400, json!({ "error": "input is not valid UTF-8" }));
+    };
+
+    let info = solver.info();
+    let start = Instant::now();
+    // Solvers return errors for malformed input, but a panic all the same (a bug, or a plugin's)
+    // is reported like any other failure rather than ending the connection
+    let result = panic::catch_unwind(AssertUnwindSafe(|| solver.solve(input)));
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let mut response = json!({
+        "year": info.year,
+        "day": info.day,
+        "part": info.part,
+        "name": info.name,
+        "elapsed_ms": elapsed_ms,
+    });
+    let status = match result {
+        Ok(Ok(solution)) => {
+            response["answer"] = json!(solution.answer);
+            response["diagnostics"] = json!(solution.diagnostics);
+            200
+        }
+        Ok(Err(error)) => {
+            response["error"] = json!(error.to_string());
+            422
+        }
+        Err(_) => {
+            response["error"] = json!("solver panicked");
+            500
+        }
+    };
+    (status, response)
+}
+
+fn write_response(stream: &mut impl Write, status: u16, body: &Value) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        422 => "Unprocessable Entity",
+        _ => "Internal Server Error",
+    };
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, body.len(), body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod serve_tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::SocketAddr;
+
+    fn start_server() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, Arc::new(Registry::builtin())));
+        address
+    }
+
+    fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}", method, path, body.len(), body).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn test_solve_examples() {
+        let address = start_server();
+        let examples = [
+            ("/2023/1/1", include_str!("../../2023/1/example_calibration_document.txt"), "142"),
+            ("/2023/1/2", include_str!("../../2023/1/example_calibration_document_2.txt"), "281"),
+            ("/2023/2/1", include_str!("../../2023/2/example_record.txt"), "8"),
+            ("/2023/2/2", include_str!("../../2023/2/example_record.txt"), "2286"),
+            ("/2023/3/1", include_str!("../../2023/3/example_schematic.txt"), "4361"),
+            ("/2023/3/2", include_str!("../../2023/3/example_schematic.txt"), "467835"),
+            ("/2023/4/1", include_str!("../../2023/4/example_cards.txt"), "13"),
+            ("/2023/4/2", include_str!("../../2023/4/example_cards.txt"), "30"),
+            ("/2023/5/2", include_str!("../../2023/5/example_almanac.txt"), "46"),
+            ("/2023/6/1", "Time:      7  15   30\nDistance:  9  40  200\n", "288"),
+            ("/2023/6/2", "Time:      71530\nDistance:  940200\n", "71503"),
+        ];
+        for (path, input, expected) in examples {
+            let (status, response) = request(address, "POST", path, input);
+            assert_eq!(status, 200, "{}: {}", path, response);
+            assert_eq!(response["answer"], expected, "{}", path);
+            assert!(response["elapsed_ms"].as_f64().unwrap() >= 0.0);
+        }
+
+        // Day 5 has no solver for part 1
+        let (status, _) = request(address, "POST", "/2023/5/1", include_str!("../../2023/5/example_almanac.txt"));
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn test_diagnostics_and_names() {
+        let address = start_server();
+        let input = include_str!("../../2023/4/example_cards.txt");
+        let (status, response) = request(address, "POST", "/2023/4/2?name=sum_cards", input);
+        assert_eq!(status, 200);
+        assert_eq!(response["name"], "sum_cards");
+        assert_eq!(response["diagnostics"][0], "0: 1");
+        assert_eq!(response["diagnostics"].as_array().unwrap().len(), 6);
+
+        let (status, _) = request(address, "POST", "/2023/4/2?name=other", input);
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn test_list_solvers() {
+        let address = start_server();
+        let (status, response) = request(address, "GET", "/solvers", "");
+        assert_eq!(status, 200);
+        let solvers = response["solvers"].as_array().unwrap();
+        assert_eq!(solvers.len(), Registry::builtin().solvers().count());
+        assert_eq!(solvers[0], json!({ "year": 2023, "day": 1, "part": 1, "name": "sum_calibration_values" }));
+    }
+
+    #[test]
+    fn test_errors() {
+        let address = start_server();
+        assert_eq!(request(address, "POST", "/2023/5/1", "").0, 404);
+        assert_eq!(request(address, "POST", "/2023/x/1", "").0, 404);
+        assert_eq!(request(address, "GET", "/2023/5/2", "").0, 405);
+        assert_eq!(request(address, "GET", "/", "").0, 404);
+
+        let (status, response) = request(address, "POST", "/2023/5/2", "seeds: 1 2\n");
+        assert_eq!(status, 422);
+        assert_eq!(response["error"], "No chain of maps from seed to location.");
+
+        let (status, response) = request(address, "POST", "/2023/6/1", "Time: 7\n");
+        assert_eq!(status, 422);
+        assert_eq!(response["error"], "expected lines of times and distances");
+    }
+}
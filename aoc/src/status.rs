@@ -0,0 +1,246 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{json, Value};
+
+use crate::all::format_table;
+use crate::history::{Answers, HistoryEntry};
+use crate::registry::Registry;
+
+/*
+ * Progress on every puzzle part: whether it has a solver registered, whether a run has given the
+ * answer recorded for it, and the fastest such run, from the registry, the recorded answers and
+ * the history of runs.
+ */
+
+const DAYS: u8 = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    // No solver registered
+    Missing,
+    // Registered, but no run has given an answer
+    Implemented,
+    // Runs have given an answer, but none the answer recorded
+    Unverified,
+    // A run has given the answer recorded
+    Verified,
+}
+
+impl Progress {
+    pub fn symbol(self) -> char {
+        match self {
+            Progress::Missing => '-',
+            Progress::Implemented => '.',
+            Progress::Unverified => '+',
+            Progress::Verified => '*',
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Progress::Missing => "missing",
+            Progress::Implemented => "implemented",
+            Progress::Unverified => "unverified",
+            Progress::Verified => "verified",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartStatus {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub progress: Progress,
+    pub solvers: Vec<String>,
+    pub answer: Option<String>,
+    pub runs: usize,
+    // Fastest run that gave an answer (the one recorded, if there is one), in ms, and its solver
+    pub best: Option<(f64, String)>,
+}
+
+impl PartStatus {
+    fn to_json(&self) -> Value {
+        json!({
+            "year": self.year,
+            "day": self.day,
+            "part": self.part,
+            "status": self.progress.label(),
+            "solvers": self.solvers,
+            "answer": self.answer,
+            "runs": self.runs,
+            "best_ms": self.best.as_ref().map(|(elapsed_ms, _)| elapsed_ms),
+            "best_solver": self.best.as_ref().map(|(_, name)| name),
+        })
+    }
+}
+
+/*
+ * The status of every part that has a solver, an answer or a run, for the year (or every year),
+ * in order of year, day and part.
+ */
+pub fn statuses(registry: &Registry, answers: &Answers, history: &[HistoryEntry], year: Option<u16>) -> Vec<PartStatus> {
+    let mut solvers: BTreeMap<(u16, u8, u8), Vec<String>> = BTreeMap::new();
+    for solver in registry.solvers() {
+        let info = solver.info();
+        solvers.entry((info.year, info.day, info.part)).or_default().push(info.name.clone());
+    }
+    let puzzles: BTreeSet<(u16, u8, u8)> = solvers.keys().copied()
+        .chain(answers.iter().map(|(puzzle, _)| puzzle))
+        .chain(history.iter().map(|entry| (entry.year, entry.day, entry.part)))
+        .filter(|&(y, _, _)| year.is_none_or(|year| y == year))
+        .collect();
+
+    puzzles.into_iter()
+        .map(|(year, day, part)| {
+            let answer = answers.get(year, day, part);
+            let runs: Vec<&HistoryEntry> = history.iter()
+                .filter(|entry| (entry.year, entry.day, entry.part) == (year, day, part))
+                .collect();
+            let correct: Vec<&HistoryEntry> = runs.iter()
+                .copied()
+                .filter(|entry| entry.answer.as_deref().is_some_and(|given| answer.is_none_or(|answer| given == answer)))
+                .collect();
+            let solvers = solvers.get(&(year, day, part)).cloned().unwrap_or_default();
+            let progress = match (answer, correct.is_empty()) {
+                (Some(_), false) => Progress::Verified,
+                _ if runs.iter().any(|entry| entry.answer.is_some()) => Progress::Unverified,
+                _ if !solvers.is_empty() => Progress::Implemented,
+                _ => Progress::Missing,
+            };
+            let best = correct.iter()
+                .min_by(|a, b| a.elapsed_ms.total_cmp(&b.elapsed_ms))
+                .map(|entry| (entry.elapsed_ms, entry.name.clone()));
+            PartStatus { year, day, part, progress, solvers, answer: answer.map(str::to_string), runs: runs.len(), best }
+        })
+        .collect()
+}
+
+/*
+ * Draws each year as a calendar of its days, with a symbol for the progress on each part, then
+ * lists every part with its solvers and best time.
+ */
+pub fn calendar(statuses: &[PartStatus]) -> String {
+    let mut output = String::new();
+    let years: BTreeSet<u16> = statuses.iter().map(|status| status.year).collect();
+    for year in years {
+        let progress = |day: u8, part: u8| {
+            statuses.iter()
+                .find(|status| (status.year, status.day, status.part) == (year, day, part))
+                .map_or(Progress::Missing, |status| status.progress)
+                .symbol()
+        };
+        let days: String = (1..=DAYS).map(|day| format!(" {:>2}", day)).collect();
+        let parts: String = (1..=DAYS).map(|day| format!(" {}{}", progress(day, 1), progress(day, 2))).collect();
+        output += &format!("{}{}\n    {}\n\n", year, days, parts);
+    }
+    output += "* verified  + unverified  . implemented  - missing\n\n";
+
+    let header = ["Puzzle", "Status", "Solvers", "Answer", "Runs", "Best time"].map(str::to_string);
+    let rows = statuses.iter()
+        .filter(|status| status.progress != Progress::Missing || status.answer.is_some())
+        .map(|status| [
+            format!("{}/{}/{}", status.year, status.day, status.part),
+            status.progress.label().to_string(),
+            if status.solvers.is_empty() { "-".to_string() } else { status.solvers.join(", ") },
+            status.answer.clone().unwrap_or_else(|| "-".to_string()),
+            status.runs.to_string(),
+            status.best.as_ref().map_or("-".to_string(), |(elapsed_ms, name)| format!("{:.3}ms ({})", elapsed_ms, name)),
+        ]);
+    output + &format_table(header, rows)
+}
+
+pub fn to_json(statuses: &[PartStatus]) -> Value {
+    Value::Array(statuses.iter().map(PartStatus::to_json).collect())
+}
+
+#[cfg(test)]
+mod status_tests {
+    use super::*;
+
+    fn run(day: u8, part: u8, name: &str, answer: Option<&str>, elapsed_ms: f64) -> HistoryEntry {
+        HistoryEntry {
+            year: 2023,
+            day,
+            part,
+            name: name.to_string(),
+            answer: answer.map(str::to_string),
+            status: if answer.is_some() { "ok" } else { "failed" }.to_string(),
+            elapsed_ms,
+            time: 1701820800,
+        }
+    }
+
+    fn example() -> Vec<PartStatus> {
+        let mut answers = Answers::default();
+        answers.insert(2023, 7, 1, "6440");
+        answers.insert(2023, 2, 1, "8");
+        answers.insert(2023, 4, 1, "13");
+        let history = [
+            run(2, 1, "sum_games", Some("8"), 0.5),
+            run(2, 1, "sum_games", Some("8"), 0.25),
+            run(2, 2, "sum_powers", Some("2286"), 0.75),
+            run(4, 1, "sum_points", Some("12"), 0.1),
+            run(4, 2, "sum_cards", None, 2.0),
+            run(2, 1, "sum_games", Some("8"), 0.5),
+        ];
+        statuses(&Registry::builtin(), &answers, &history, Some(2023))
+    }
+
+    #[test]
+    fn test_statuses() {
+        let summary: Vec<(String, Progress, usize, Option<f64>)> = example().iter()
+            .map(|status| (
+                format!("{}/{}", status.day, status.part),
+                status.progress,
+                status.runs,
+                status.best.as_ref().map(|(elapsed_ms, _)| *elapsed_ms),
+            ))
+            .collect();
+        assert_eq!(summary, vec![
+            ("1/1".to_string(), Progress::Implemented, 0, None),
+            ("1/2".to_string(), Progress::Implemented, 0, None),
+            ("2/1".to_string(), Progress::Verified, 3, Some(0.25)),
+            ("2/2".to_string(), Progress::Unverified, 1, Some(0.75)),
+            ("3/1".to_string(), Progress::Implemented, 0, None),
+            ("3/2".to_string(), Progress::Implemented, 0, None),
+            ("4/1".to_string(), Progress::Unverified, 1, None),
+            ("4/2".to_string(), Progress::Implemented, 1, None),
+            ("5/2".to_string(), Progress::Implemented, 0, None),
+            ("6/1".to_string(), Progress::Implemented, 0, None),
+            ("6/2".to_string(), Progress::Implemented, 0, None),
+            ("7/1".to_string(), Progress::Missing, 0, None),
+        ]);
+        assert!(statuses(&Registry::builtin(), &Answers::default(), &[], Some(2022)).is_empty());
+    }
+
+    #[test]
+    fn test_calendar() {
+        let calendar = calendar(&example());
+        let lines: Vec<&str> = calendar.lines().collect();
+        assert_eq!(lines[0], "2023  1  2  3  4  5  6  7  8  9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25");
+        assert_eq!(lines[1], "     .. *+ .. +. -. .. -- -- -- -- -- -- -- -- -- -- -- -- -- -- -- -- -- -- --");
+        assert_eq!(lines[3], "* verified  + unverified  . implemented  - missing");
+        assert_eq!(lines[5], "Puzzle    Status       Solvers                                          Answer  Runs  Best time");
+        assert_eq!(lines[6], "2023/1/1  implemented  sum_calibration_values                           -       0     -");
+        assert_eq!(lines[8], "2023/2/1  verified     sum_games                                        8       3     0.250ms (sum_games)");
+        assert_eq!(lines[17], "2023/7/1  missing      -                                                6440    0     -");
+    }
+
+    #[test]
+    fn test_json() {
+        let json = to_json(&example());
+        assert_eq!(json[2], json!({
+            "year": 2023,
+            "day": 2,
+            "part": 1,
+            "status": "verified",
+            "solvers": ["sum_games"],
+            "answer": "8",
+            "runs": 3,
+            "best_ms": 0.25,
+            "best_solver": "sum_games",
+        }));
+        assert_eq!(json[0]["best_ms"], Value::Null);
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyInt;
+
+use common::frames::Recording;
+use common::solver::SolveError;
+
+/*
+ * Python extension module exposing the solvers of each day and their reusable data types.
+ *
+ * Solvers take the whole puzzle input as a string and return a Solution. Errors they report are
+ * raised as ValueError.
+ */
+
+/*
+ * The answer as a Python int (so unbounded even when built without the bigint feature), with the
+ * solver's diagnostics.
+ */
+#[pyclass(frozen, name = "Solution")]
+struct PySolution {
+    #[pyo3(get)]
+    answer: Py<PyInt>,
+    #[pyo3(get)]
+    diagnostics: Vec<String>,
+}
+
+#[pymethods]
+impl PySolution {
+    fn __repr__(&self, py: Python<'_>) -> String {
+        format!("Solution(answer={}, diagnostics=[{} lines])", self.answer.bind(py), self.diagnostics.len())
+    }
+}
+
+fn to_python(py: Python<'_>, result: Result<common::solver::Solution, SolveError>) -> PyResult<PySolution> {
+    let solution = result.map_err(|error| PyValueError::new_err(error.to_string()))?;
+    let answer = py.get_type::<PyInt>().call1((solution.answer,))?.downcast_into::<PyInt>()?;
+    Ok(PySolution { answer: answer.unbind(), diagnostics: solution.diagnostics })
+}
+
+// Day 1
+
+// The puzzle's own mode for a part, as the runner registers it
+fn calibration_mode(part: u8) -> PyResult<day1::Mode> {
+    match part {
+        1 | 2 => Ok(day1::Mode::part(part)),
+        _ => Err(PyValueError::new_err(format!("no part {}, only 1 and 2", part))),
+    }
+}
+
+/*
+ * The calibration value of a line for the part, or None if it has no digits.
+ */
+#[pyfunction]
+fn calibration_value(line: &str, part: u8) -> PyResult<Option<u64>> {
+    let calibration = day1::calibration_value(line, &calibration_mode(part)?).map_err(|error| PyValueError::new_err(error.to_string()))?;
+    Ok(calibration.map(|calibration| calibration.value))
+}
+
+#[pyfunction]
+fn sum_calibration_document(py: Python<'_>, input: &str, part: u8) -> PyResult<PySolution> {
+    let mode = calibration_mode(part)?;
+    to_python(py, day1::sum_calibration_document(input.as_bytes(), &mode, day1::line_summary::LineCheck::Off))
+}
+
+// Day 2
+
+#[pyfunction]
+fn sum_games(py: Python<'_>, input: &str) -> PyResult<PySolution> {
+    to_python(py, day2::sum_games(input.as_bytes()))
+}
+
+#[pyfunction]
+fn sum_powers(py: Python<'_>, input: &str) -> PyResult<PySolution> {
+    to_python(py, day2::sum_powers(input.as_bytes()))
+}
+
+// Day 3
+
+#[pyfunction]
+fn sum_parts(py: Python<'_>, input: &str) -> PyResult<PySolution> {
+    to_python(py, day3::parts::sum_parts(input.as_bytes(), &mut Recording::disabled()))
+}
+
+#[pyfunction]
+fn sum_gears(py: Python<'_>, input: &str) -> PyResult<PySolution> {
+    to_python(py, day3::gears::sum_gears(input.as_bytes()))
+}
+
+// Day 4
+
+#[pyfunction]
+fn match_count(line: &str) -> usize {
+    day4::match_count(line.as_bytes())
+}
+
+#[pyfunction]
+fn sum_points(py: Python<'_>, input: &str) -> PyResult<PySolution> {
+    to_python(py, day4::sum_points(input.as_bytes()))
+}
+
+#[pyfunction]
+fn sum_cards(py: Python<'_>, input: &str) -> PyResult<PySolution> {
+    to_python(py, day4::sum_cards(input.as_bytes()))
+}
+
+// Day 5
+
+#[pyfunction]
+fn parse_key_values_config(input: &str) -> HashMap<String, Vec<String>> {
+    day5::parse_key_values_config(input)
+}
+
+/*
+ * Maps keys in the intervals inserted to the same offset from the interval's value, and any other
+ * key to itself.
+ */
+#[pyclass(name = "OffsetIntervalMap")]
+#[derive(Default)]
+struct PyOffsetIntervalMap(day5::OffsetIntervalMap);
+
+#[pymethods]
+impl PyOffsetIntervalMap {
+    #[new]
+    fn new() -> Self {
+        PyOffsetIntervalMap(day5::OffsetIntervalMap::new())
+    }
+
+    /*
+     * Maps the interval start (inclusive) to end (exclusive) onto value upwards.
+     */
+    fn insert(&mut self, start: i64, end: i64, value: i64) {
+        self.0.insert(start..end, value);
+    }
+
+    fn get(&self, key: i64) -> Option<i64> {
+        self.0.get(key)
+    }
+
+    /*
+     * Returns the mapped value and the one-based id of the interval used, or 0 if none was.
+     */
+    fn get_with_interval(&self, key: i64) -> Option<(i64, usize)> {
+        self.0.get_with_interval(key)
+    }
+}
+
+#[pyfunction]
+fn ranges_succession_path(chain: Vec<PyRef<'_, PyOffsetIntervalMap>>, start_value: i64) -> (i64, Vec<i64>) {
+    let maps: Vec<&day5::OffsetIntervalMap> = chain.iter().map(|map| &map.0).collect();
+    day5::ranges_succession_path(&maps, start_value)
+}
+
+#[pyfunction]
+fn map_seeds(py: Python<'_>, input: &str) -> PyResult<PySolution> {
+    to_python(py, day5::map_seeds(input, &mut Recording::disabled()))
+}
+
+// Day 6
+
+#[pyfunction]
+fn numbers_from_string(string: &str) -> Vec<u64> {
+    day6::numbers_from_string(string)
+}
+
+#[pyfunction]
+fn count_ways(time: u64, distance: u64) -> u64 {
+    day6::count_ways(time, distance)
+}
+
+#[pyfunction]
+fn product_of_ways_count(py: Python<'_>, input: &str) -> PyResult<PySolution> {
+    to_python(py, day6::product_of_ways_count(input.as_bytes()))
+}
+
+#[pymodule]
+fn aoc2023(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PySolution>()?;
+    module.add_function(wrap_pyfunction!(calibration_value, module)?)?;
+    module.add_function(wrap_pyfunction!(sum_calibration_document, module)?)?;
+    module.add_function(wrap_pyfunction!(sum_games, module)?)?;
+    module.add_function(wrap_pyfunction!(sum_powers, module)?)?;
+    module.add_function(wrap_pyfunction!(sum_parts, module)?)?;
+    module.add_function(wrap_pyfunction!(sum_gears, module)?)?;
+    module.add_function(wrap_pyfunction!(match_count, module)?)?;
+    module.add_function(wrap_pyfunction!(sum_points, module)?)?;
+    module.add_function(wrap_pyfunction!(sum_cards, module)?)?;
+    module.add_function(wrap_pyfunction!(parse_key_values_config, module)?)?;
+    module.add_class::<PyOffsetIntervalMap>()?;
+    module.add_function(wrap_pyfunction!(ranges_succession_path, module)?)?;
+    module.add_function(wrap_pyfunction!(map_seeds, module)?)?;
+    module.add_function(wrap_pyfunction!(numbers_from_string, module)?)?;
+    module.add_function(wrap_pyfunction!(count_ways, module)?)?;
+    module.add_function(wrap_pyfunction!(product_of_ways_count, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,82 @@
+from pathlib import Path
+
+import pytest
+
+import aoc2023
+
+DAYS = Path(__file__).resolve().parents[2] / "2023"
+
+
+def example(day, name):
+    return (DAYS / str(day) / name).read_text()
+
+
+@pytest.mark.parametrize("solve, day, name, expected", [
+    (lambda input: aoc2023.sum_calibration_document(input, 1), 1, "example_calibration_document.txt", 142),
+    (lambda input: aoc2023.sum_calibration_document(input, 2), 1, "example_calibration_document_2.txt", 281),
+    (aoc2023.sum_games, 2, "example_record.txt", 8),
+    (aoc2023.sum_powers, 2, "example_record.txt", 2286),
+    (aoc2023.sum_parts, 3, "example_schematic.txt", 4361),
+    (aoc2023.sum_gears, 3, "example_schematic.txt", 467835),
+    (aoc2023.sum_points, 4, "example_cards.txt", 13),
+    (aoc2023.sum_cards, 4, "example_cards.txt", 30),
+    (aoc2023.map_seeds, 5, "example_almanac.txt", 46),
+])
+def test_examples(solve, day, name, expected):
+    solution = solve(example(day, name))
+    assert solution.answer == expected
+    assert isinstance(solution.answer, int)
+
+
+def test_diagnostics():
+    solution = aoc2023.sum_cards(example(4, "example_cards.txt"))
+    assert solution.diagnostics == ["0: 1", "1: 2", "2: 4", "3: 8", "4: 14", "5: 1"]
+
+
+def test_calibration_value():
+    assert aoc2023.calibration_value("treb7uchet", 1) == 77
+    assert aoc2023.calibration_value("xtwone3four", 1) == 33
+    assert aoc2023.calibration_value("xtwone3four", 2) == 24
+    assert aoc2023.calibration_value("abc", 1) is None
+    with pytest.raises(ValueError, match="no part 3"):
+        aoc2023.calibration_value("1", 3)
+
+
+def test_match_count():
+    lines = example(4, "example_cards.txt").splitlines()
+    assert [aoc2023.match_count(line) for line in lines] == [4, 2, 2, 1, 0, 0]
+
+
+def test_parse_key_values_config():
+    config = aoc2023.parse_key_values_config(example(5, "example_almanac.txt"))
+    assert config["seeds"] == ["79 14 55 13"]
+    assert config["seed-to-soil map"] == ["50 98 2", "52 50 48"]
+    assert len(config) == 8
+
+
+def test_offset_interval_map():
+    offset_map = aoc2023.OffsetIntervalMap()
+    offset_map.insert(0, 5, 10)
+    offset_map.insert(20, 30, 50)
+    assert offset_map.get(3) == 13
+    assert offset_map.get(40) == 40
+    assert offset_map.get_with_interval(25) == (55, 2)
+    assert offset_map.get_with_interval(40) == (40, 0)
+
+    doubled = aoc2023.OffsetIntervalMap()
+    doubled.insert(10, 20, 100)
+    assert aoc2023.ranges_succession_path([offset_map, doubled], 3) == (103, [1, 1])
+
+
+def test_races():
+    assert aoc2023.numbers_from_string("Time:      7  15   30") == [7, 15, 30]
+    assert [aoc2023.count_ways(7, 9), aoc2023.count_ways(15, 40), aoc2023.count_ways(30, 200)] == [4, 8, 9]
+    solution = aoc2023.product_of_ways_count("Time:      7  15   30\nDistance:  9  40  200\n")
+    assert solution.answer == 288
+
+
+def test_errors_raise_value_error():
+    with pytest.raises(ValueError, match="No chain of maps"):
+        aoc2023.map_seeds("seeds: 1 2\n")
+    with pytest.raises(ValueError, match="expected lines of times and distances"):
+        aoc2023.product_of_ways_count("Time: 7\n")